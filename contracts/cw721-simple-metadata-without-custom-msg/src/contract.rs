@@ -1,14 +1,11 @@
 use crate::error::ContractError;
 use crate::execute::{invalid_hello, valid_hello};
-use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::msg::{CustomExecuteMsg, CustomQueryMsg, ExecuteMsg, QueryMsg};
 use crate::query::handle_custom_query_msg;
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw721_base::msg::InstantiateMsg;
-use cw721_simple_base::contract::{
-    execute as cw721_execute, instantiate as cw721_instantiate, query as cw721_query,
-};
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+use cw721_simple_base::error::CustomError;
+use cw721_simple_base::extension::Cw721Extension;
+use cw721_simple_derive::cw721_entry_points;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -37,45 +34,41 @@ pub struct Metadata {
 
 pub type Extension = Option<Metadata>;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
-    let res = cw721_instantiate(deps, env, info, msg);
-    match res {
-        Ok(res) => Ok(res),
-        Err(err) => Err(ContractError::Cw721ContractError(err)),
-    }
+/// Custom messages are flattened into crate::msg instead of ExecuteMsg::Extension,
+/// so only the metadata type is plugged into cw721-simple-base
+#[derive(Default)]
+pub struct MetadataContract;
+
+impl Cw721Extension for MetadataContract {
+    type Extension = Extension;
+    type ExecuteMsg = Empty;
+    type QueryMsg = Empty;
+    type CustomMsg = Empty;
+    type Error = CustomError;
+}
+
+cw721_entry_points! {
+    extension: MetadataContract,
+    execute: ExecuteMsg => execute_custom,
+    query: QueryMsg => query_custom,
+    error: ContractError,
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
+fn execute_custom(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: CustomExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::DefaultCw721ExecuteMsg(msg) => {
-            let res = cw721_execute::<Extension, _, _, _>(deps, env, info, *msg);
-            match res {
-                Ok(res) => Ok(res),
-                Err(err) => Err(ContractError::Cw721ContractError(err)),
-            }
-        }
-        ExecuteMsg::ValidHello {} => valid_hello(),
-        ExecuteMsg::InvalidHello {} => invalid_hello(),
+        CustomExecuteMsg::ValidHello {} => valid_hello(),
+        CustomExecuteMsg::InvalidHello {} => invalid_hello(),
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+fn query_custom(_deps: Deps, _env: Env, msg: CustomQueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Cw721QueryMsg(msg) => cw721_query::<Extension, _>(deps, env, msg),
-        QueryMsg::HelloQuery { value } => handle_custom_query_msg(value),
+        CustomQueryMsg::HelloQuery { value } => handle_custom_query_msg(value),
     }
 }
 
@@ -83,14 +76,16 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 pub mod test_contract {
     use crate::contract::{execute, instantiate, query, Extension, Metadata, Trait};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, QueryMsg};
+    use crate::msg::{CustomExecuteMsg, CustomQueryMsg, ExecuteMsg, QueryMsg};
     use crate::query::HelloResponse;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary, DepsMut, Response};
+    use cosmwasm_std::{attr, from_binary, from_slice, DepsMut, Response};
     use cw721::{NftInfoResponse, OwnerOfResponse};
-    use cw721_base::msg::{
+    use cw721_simple_base::error::ContractError as Cw721ContractError;
+    use cw721_simple_base::msg::{
         ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg as Cw721QueryMsg,
     };
+    use cw721_simple_conformance::Cw721Suite;
 
     const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
 
@@ -100,47 +95,77 @@ pub mod test_contract {
             mock_env(),
             mock_info(ADDR1, &[]),
             InstantiateMsg {
-                name: "cw721-contract".to_string(),
-                symbol: "cw721".to_string(),
                 minter: ADDR1.to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
     }
 
     fn mint(deps: DepsMut, owner: &str, token_id: &str) -> Result<Response, ContractError> {
-        let execute_mint_msg =
-            ExecuteMsg::DefaultCw721ExecuteMsg(Box::new(Cw721ExecuteMsg::Mint(MintMsg {
-                token_id: token_id.to_string(),
-                owner: owner.to_string(),
-                token_uri: None,
-                extension: Some(Metadata {
-                    image: Some("image".to_string()),
-                    image_data: Some("image_data".to_string()),
-                    external_url: Some("external_url".to_string()),
-                    description: Some("description".to_string()),
-                    name: Some("name".to_string()),
-                    attributes: Some(vec![Trait {
-                        display_type: Some("display_type".to_string()),
-                        trait_type: "trait_type".to_string(),
-                        value: "value".to_string(),
-                    }]),
-                    background_color: Some("background_color".to_string()),
-                    animation_url: Some("animation_url".to_string()),
-                    youtube_url: Some("youtube_url".to_string()),
-                }),
-            })));
+        let execute_mint_msg = ExecuteMsg::Cw721(Cw721ExecuteMsg::Mint(MintMsg {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                image: Some("image".to_string()),
+                image_data: Some("image_data".to_string()),
+                external_url: Some("external_url".to_string()),
+                description: Some("description".to_string()),
+                name: Some("name".to_string()),
+                attributes: Some(vec![Trait {
+                    display_type: Some("display_type".to_string()),
+                    trait_type: "trait_type".to_string(),
+                    value: "value".to_string(),
+                }]),
+                background_color: Some("background_color".to_string()),
+                animation_url: Some("animation_url".to_string()),
+                youtube_url: Some("youtube_url".to_string()),
+            }),
+        }));
 
         execute(deps, mock_env(), mock_info(ADDR1, &[]), execute_mint_msg)
     }
 
+    #[test]
+    fn test_conformance() {
+        Cw721Suite {
+            instantiate,
+            execute,
+            query,
+            extensions: vec![
+                None,
+                Some(Metadata {
+                    image: None,
+                    image_data: None,
+                    external_url: None,
+                    description: None,
+                    name: Some("conformance".to_string()),
+                    attributes: Some(vec![]),
+                    background_color: None,
+                    animation_url: None,
+                    youtube_url: None,
+                }),
+            ],
+        }
+        .run_all();
+    }
+
     #[test]
     fn test_mint() {
         let mut deps = mock_dependencies();
         init(deps.as_mut());
         mint(deps.as_mut(), ADDR1, "1").unwrap();
 
-        let owner_of_query_msg = QueryMsg::Cw721QueryMsg(Cw721QueryMsg::OwnerOf {
+        // base errors are converted by the derived From and keep their message
+        let claimed_err = mint(deps.as_mut(), ADDR1, "1").unwrap_err();
+        assert_eq!(
+            claimed_err,
+            ContractError::Cw721ContractError(Cw721ContractError::Claimed {})
+        );
+        assert_eq!(claimed_err.to_string(), "token_id already claimed");
+
+        let owner_of_query_msg = QueryMsg::Cw721(Cw721QueryMsg::OwnerOf {
             token_id: "1".to_string(),
             include_expired: Some(false),
         });
@@ -155,7 +180,7 @@ pub mod test_contract {
             }
         );
 
-        let nft_info_query_msg = QueryMsg::Cw721QueryMsg(Cw721QueryMsg::NftInfo {
+        let nft_info_query_msg = QueryMsg::Cw721(Cw721QueryMsg::NftInfo {
             token_id: "1".to_string(),
         });
 
@@ -189,7 +214,7 @@ pub mod test_contract {
     fn test_customs() {
         let mut deps = mock_dependencies();
 
-        let valid_hello_msg = ExecuteMsg::ValidHello {};
+        let valid_hello_msg = ExecuteMsg::Custom(CustomExecuteMsg::ValidHello {});
         let valid_hello_res = execute(
             deps.as_mut(),
             mock_env(),
@@ -203,7 +228,7 @@ pub mod test_contract {
             [attr("custom_msg", "valid_hello")]
         );
 
-        let invalid_hello_msg = ExecuteMsg::InvalidHello {};
+        let invalid_hello_msg = ExecuteMsg::Custom(CustomExecuteMsg::InvalidHello {});
         let invalid_hello_err = execute(
             deps.as_mut(),
             mock_env(),
@@ -219,9 +244,9 @@ pub mod test_contract {
             }
         );
 
-        let hello_query_msg = QueryMsg::HelloQuery {
+        let hello_query_msg = QueryMsg::Custom(CustomQueryMsg::HelloQuery {
             value: "hello_query".to_string(),
-        };
+        });
         let hello_query_res: HelloResponse =
             from_binary(&query(deps.as_ref(), mock_env(), hello_query_msg).unwrap()).unwrap();
 
@@ -232,4 +257,26 @@ pub mod test_contract {
             }
         );
     }
+
+    #[test]
+    fn test_flat_json() {
+        // both kinds of messages keep the JSON of their own enum
+        let msg: ExecuteMsg = from_slice(br#"{"valid_hello":{}}"#).unwrap();
+        assert_eq!(msg, ExecuteMsg::Custom(CustomExecuteMsg::ValidHello {}));
+        let msg: ExecuteMsg = from_slice(br#"{"burn":{"token_id":"1"}}"#).unwrap();
+        assert_eq!(
+            msg,
+            ExecuteMsg::Cw721(Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string()
+            })
+        );
+        let msg: QueryMsg = from_slice(br#"{"hello_query":{"value":"hi"}}"#).unwrap();
+        assert_eq!(
+            msg,
+            QueryMsg::Custom(CustomQueryMsg::HelloQuery {
+                value: "hi".to_string()
+            })
+        );
+        assert!(from_slice::<ExecuteMsg>(br#"{"unknown":{}}"#).is_err());
+    }
 }
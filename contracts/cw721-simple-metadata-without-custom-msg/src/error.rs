@@ -1,12 +1,14 @@
 use cw721_simple_base::error::ContractError as Cw721ContractError;
+use cw721_simple_derive::Cw721Error;
 use std::fmt::Debug;
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Cw721Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("HelloError: {msg}")]
     HelloError { msg: String },
 
-    #[error("Cw721ContractError")]
+    #[cw721]
+    #[error("{0}")]
     Cw721ContractError(Cw721ContractError),
 }
@@ -1,20 +1,20 @@
-use crate::contract::Extension;
-use cosmwasm_std::Empty;
-use cw721_base::msg::{ExecuteMsg as Cw721ExecuteMsg, QueryMsg as Cw721QueryMsg};
+use crate::contract::MetadataContract;
+use cw721_simple_derive::{cw721_execute, cw721_query};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// cw721 base messages and the custom ones, which are moved into CustomExecuteMsg
+#[cw721_execute(MetadataContract)]
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    DefaultCw721ExecuteMsg(Box<Cw721ExecuteMsg<Extension, Empty>>),
     ValidHello {},
     InvalidHello {},
 }
 
+#[cw721_query(MetadataContract)]
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Cw721QueryMsg(Cw721QueryMsg<Empty>),
     HelloQuery { value: String },
 }
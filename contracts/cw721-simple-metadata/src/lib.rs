@@ -1,11 +1,9 @@
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw721_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use cw721_simple_base::contract::{
-    execute as cw721_execute, instantiate as cw721_instantiate, query as cw721_query,
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
 };
 use cw721_simple_base::error::ContractError;
+use cw721_simple_base::extension::Cw721Extension;
+use cw721_simple_derive::cw721_entry_points;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -58,68 +56,61 @@ pub struct Metadata {
 
 pub type Extension = Option<Metadata>;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
-    cw721_instantiate(deps, env, info, msg)
-}
+/// Routes the custom messages of cw721-simple-metadata through the cw721-simple-base hooks
+#[derive(Default)]
+pub struct MetadataExtension;
 
-fn handle_custom_msg(msg: CustomExtensionMsg) -> Result<Response, ContractError<CustomError>> {
-    match msg {
-        CustomExtensionMsg::ValidHello {} => {
-            Ok(Response::new().add_attribute("custom_msg", "hello"))
-        }
-        CustomExtensionMsg::InvalidHello {} => {
-            Err(ContractError::CustomError(CustomError::HelloError {
-                msg: "no_hello".to_string(),
-            }))
+impl Cw721Extension for MetadataExtension {
+    type Extension = Extension;
+    type ExecuteMsg = CustomExtensionMsg;
+    type QueryMsg = CustomQuery;
+    type CustomMsg = Empty;
+    type Error = CustomError;
+
+    fn execute_extension(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: CustomExtensionMsg,
+    ) -> Result<Response, ContractError<CustomError>> {
+        match msg {
+            CustomExtensionMsg::ValidHello {} => {
+                Ok(Response::new().add_attribute("custom_msg", "hello"))
+            }
+            CustomExtensionMsg::InvalidHello {} => {
+                Err(ContractError::CustomError(CustomError::HelloError {
+                    msg: "no_hello".to_string(),
+                }))
+            }
         }
     }
-}
 
-fn handle_custom_query_msg(msg: CustomQuery) -> StdResult<Binary> {
-    match msg {
-        CustomQuery::HelloQuery {} => to_binary(&HelloResponse {
-            msg: "custom_hello_query_response".to_string(),
-        }),
-    }
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg<Extension, CustomExtensionMsg>,
-) -> Result<Response, ContractError<CustomError>> {
-    match msg {
-        ExecuteMsg::Extension { msg } => handle_custom_msg(msg),
-        _ => cw721_execute(deps, env, info, msg),
+    fn query_extension(&self, _deps: Deps, _env: Env, msg: CustomQuery) -> StdResult<Binary> {
+        match msg {
+            CustomQuery::HelloQuery {} => to_binary(&HelloResponse {
+                msg: "custom_hello_query_response".to_string(),
+            }),
+        }
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg<CustomQuery>) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Extension { msg } => handle_custom_query_msg(msg),
-        _ => cw721_query::<Extension, CustomQuery>(deps, env, msg),
-    }
+cw721_entry_points! {
+    extension: MetadataExtension,
 }
 
 #[cfg(test)]
 pub mod test_contract {
     use crate::{
-        execute, instantiate, query, CustomError, CustomExtensionMsg, Extension, Metadata, Trait,
+        execute, instantiate, query, CustomError, CustomExtensionMsg, CustomQuery, Extension,
+        HelloResponse, Metadata, Trait,
     };
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{from_binary, DepsMut, Response};
+    use cosmwasm_std::{attr, from_binary, from_slice, DepsMut, Response};
     use cw721::{NftInfoResponse, OwnerOfResponse};
-    use cw721_base::msg::{ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg};
     use cw721_simple_base::error::ContractError;
+    use cw721_simple_base::msg::{ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg};
+    use cw721_simple_conformance::Cw721Suite;
 
     const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
 
@@ -129,9 +120,8 @@ pub mod test_contract {
             mock_env(),
             mock_info(ADDR1, &[]),
             InstantiateMsg {
-                name: "cw721-contract".to_string(),
-                symbol: "cw721".to_string(),
                 minter: ADDR1.to_string(),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -167,6 +157,30 @@ pub mod test_contract {
         execute(deps, mock_env(), mock_info(ADDR1, &[]), execute_mint_msg)
     }
 
+    #[test]
+    fn test_conformance() {
+        Cw721Suite {
+            instantiate,
+            execute,
+            query,
+            extensions: vec![
+                None,
+                Some(Metadata {
+                    image: None,
+                    image_data: None,
+                    external_url: None,
+                    description: None,
+                    name: Some("conformance".to_string()),
+                    attributes: Some(vec![]),
+                    background_color: None,
+                    animation_url: None,
+                    youtube_url: None,
+                }),
+            ],
+        }
+        .run_all();
+    }
+
     #[test]
     fn test_mint() {
         let mut deps = mock_dependencies();
@@ -217,4 +231,136 @@ pub mod test_contract {
             }
         );
     }
+
+    #[test]
+    fn test_extension_msgs() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+
+        let valid_hello_res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            ExecuteMsg::Extension {
+                msg: CustomExtensionMsg::ValidHello {},
+            },
+        )
+        .unwrap();
+        assert_eq!(valid_hello_res.attributes, [attr("custom_msg", "hello")]);
+
+        let invalid_hello_err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            ExecuteMsg::Extension {
+                msg: CustomExtensionMsg::InvalidHello {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            invalid_hello_err,
+            ContractError::CustomError(CustomError::HelloError {
+                msg: "no_hello".to_string()
+            })
+        );
+
+        let hello_query_res: HelloResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Extension {
+                    msg: CustomQuery::HelloQuery {},
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hello_query_res,
+            HelloResponse {
+                msg: "custom_hello_query_response".to_string()
+            }
+        );
+    }
+
+    /// own messages flattened next to an extension with its own ExecuteMsg and QueryMsg
+    mod flattened {
+        use crate::{CustomError, MetadataExtension};
+        use cosmwasm_std::{
+            to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+        };
+        use cw721_simple_base::error::ContractError;
+        use cw721_simple_derive::{cw721_entry_points, cw721_execute, cw721_query};
+        use schemars::JsonSchema;
+        use serde::{Deserialize, Serialize};
+
+        #[cw721_execute(MetadataExtension)]
+        #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+        #[serde(rename_all = "snake_case")]
+        pub enum ExecuteMsg {
+            Ping {},
+        }
+
+        #[cw721_query(MetadataExtension)]
+        #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+        #[serde(rename_all = "snake_case")]
+        pub enum QueryMsg {
+            Pong {},
+        }
+
+        cw721_entry_points! {
+            extension: MetadataExtension,
+            execute: ExecuteMsg => execute_custom,
+            query: QueryMsg => query_custom,
+        }
+
+        fn execute_custom(
+            _deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            msg: CustomExecuteMsg,
+        ) -> Result<Response, ContractError<CustomError>> {
+            match msg {
+                CustomExecuteMsg::Ping {} => {
+                    Ok(Response::new().add_attribute("custom_msg", "ping"))
+                }
+            }
+        }
+
+        fn query_custom(_deps: Deps, _env: Env, msg: CustomQueryMsg) -> StdResult<Binary> {
+            match msg {
+                CustomQueryMsg::Pong {} => to_binary("pong"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_flattened_msgs_with_extension_msgs() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        let run = |deps: DepsMut, json: &[u8]| {
+            let msg: flattened::ExecuteMsg = from_slice(json).unwrap();
+            flattened::execute(deps, mock_env(), mock_info(ADDR1, &[]), msg)
+        };
+
+        // extension messages reach the Cw721Extension, the own ones the custom handler
+        let res = run(
+            deps.as_mut(),
+            br#"{"extension":{"msg":{"valid_hello":{}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(res.attributes, [attr("custom_msg", "hello")]);
+        let res = run(deps.as_mut(), br#"{"ping":{}}"#).unwrap();
+        assert_eq!(res.attributes, [attr("custom_msg", "ping")]);
+
+        let msg: flattened::QueryMsg =
+            from_slice(br#"{"extension":{"msg":{"HelloQuery":{}}}}"#).unwrap();
+        let res: HelloResponse =
+            from_binary(&flattened::query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.msg, "custom_hello_query_response");
+        let msg = flattened::QueryMsg::Custom(flattened::CustomQueryMsg::Pong {});
+        let res: String =
+            from_binary(&flattened::query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res, "pong");
+    }
 }
@@ -1,36 +1,40 @@
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-
-use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
-use cw721_base::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use cw721_simple_base::contract::{
-    execute as cw721_execute, instantiate as cw721_instantiate, query as cw721_query,
-};
-use cw721_simple_base::error::ContractError;
+use cosmwasm_std::Empty;
+use cw721_simple_base::error::CustomError;
+use cw721_simple_base::extension::Cw721Extension;
+use cw721_simple_derive::cw721_entry_points;
 
 type Extension = Option<Empty>;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
-    cw721_instantiate(deps, env, info, msg)
+/// cw721-simple doesn't hook into anything, every method of Cw721Extension keeps its default
+#[derive(Default)]
+pub struct SimpleExtension;
+
+impl Cw721Extension for SimpleExtension {
+    type Extension = Extension;
+    type ExecuteMsg = Empty;
+    type QueryMsg = Empty;
+    type CustomMsg = Empty;
+    type Error = CustomError;
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg<Extension, Empty>,
-) -> Result<Response, ContractError> {
-    cw721_execute(deps, env, info, msg)
+cw721_entry_points! {
+    extension: SimpleExtension,
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg<Empty>) -> StdResult<Binary> {
-    cw721_query::<Extension, Empty>(deps, env, msg)
+#[cfg(test)]
+pub mod test_contract {
+    use crate::{execute, instantiate, query};
+    use cosmwasm_std::Empty;
+    use cw721_simple_conformance::Cw721Suite;
+
+    #[test]
+    fn test_conformance() {
+        Cw721Suite {
+            instantiate,
+            execute,
+            query,
+            extensions: vec![None, Some(Empty {})],
+        }
+        .run_all();
+    }
 }
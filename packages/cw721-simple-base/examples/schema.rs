@@ -1,6 +1,12 @@
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas};
 use cosmwasm_std::Empty;
-use cw721_base::msg::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
+use cw721_simple_base::msg::{
+    AddressListResponse, ClaimableResponse, CollectionInfoResponse, Cw20HookMsg,
+    Cw20PricesResponse, Cw20ProceedsResponse, ExecuteMsg, FrozenTokensResponse, InstantiateMsg,
+    LockedTokensResponse, MigrateMsg, MintMsg, MinterResponse, NativePriceResponse, PayeesResponse,
+    PendingTransferResponse, PendingTransfersResponse, QueryMsg, ScopedGrantResponse,
+    ScopedGrantsResponse, StatsResponse, TokenHistoryResponse, TransferFeeResponse,
+};
 use schemars::schema_for;
 use std::env::current_dir;
 use std::fs::create_dir_all;
@@ -13,7 +19,11 @@ fn main() {
     create_dir_all(&out_dir).unwrap();
     remove_schemas(&out_dir).unwrap();
 
-    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema_with_title(
+        &schema_for!(InstantiateMsg<Extension>),
+        &out_dir,
+        "InstantiateMsg",
+    );
     export_schema_with_title(
         &schema_for!(ExecuteMsg<Extension, Empty>),
         &out_dir,
@@ -21,5 +31,27 @@ fn main() {
     );
     export_schema_with_title(&schema_for!(MintMsg<Extension>), &out_dir, "MintMsg");
     export_schema_with_title(&schema_for!(QueryMsg<Extension>), &out_dir, "QueryMsg");
+    export_schema_with_title(
+        &schema_for!(Cw20HookMsg<Extension>),
+        &out_dir,
+        "Cw20HookMsg",
+    );
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(MinterResponse), &out_dir);
+    export_schema(&schema_for!(CollectionInfoResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(TransferFeeResponse), &out_dir);
+    export_schema(&schema_for!(Cw20PricesResponse), &out_dir);
+    export_schema(&schema_for!(Cw20ProceedsResponse), &out_dir);
+    export_schema(&schema_for!(NativePriceResponse), &out_dir);
+    export_schema(&schema_for!(PayeesResponse), &out_dir);
+    export_schema(&schema_for!(ClaimableResponse), &out_dir);
+    export_schema(&schema_for!(TokenHistoryResponse), &out_dir);
+    export_schema(&schema_for!(PendingTransferResponse), &out_dir);
+    export_schema(&schema_for!(PendingTransfersResponse), &out_dir);
+    export_schema(&schema_for!(ScopedGrantResponse), &out_dir);
+    export_schema(&schema_for!(ScopedGrantsResponse), &out_dir);
+    export_schema(&schema_for!(AddressListResponse), &out_dir);
+    export_schema(&schema_for!(FrozenTokensResponse), &out_dir);
+    export_schema(&schema_for!(LockedTokensResponse), &out_dir);
 }
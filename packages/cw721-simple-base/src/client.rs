@@ -0,0 +1,1055 @@
+use crate::msg::{
+    AddressListResponse, ClaimableResponse, CollectionInfoMsg, CollectionInfoResponse,
+    Cw20PricesResponse, Cw20ProceedsResponse, ExecuteMsg, FrozenTokensResponse,
+    LockedTokensResponse, MintMsg, MinterResponse, NativePriceResponse, PayeeMsg, PayeesResponse,
+    PendingTransferResponse, PendingTransfersResponse, QueryMsg, ScopedGrantResponse,
+    ScopedGrantsResponse, StatsResponse, TokenHistoryResponse, TransferFeeMsg, TransferFeeResponse,
+};
+use crate::state::{AddressListMode, GrantScope};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Empty, QuerierWrapper, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw721::{
+    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
+    CustomMsg, Expiration, NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+    TokensResponse,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// Typed helper for contracts integrating with a collection built on cw721-simple-base.
+/// T is the metadata extension, E and Q the custom messages of ExecuteMsg::Extension and
+/// QueryMsg::Extension, same as the associated types of Cw721Extension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cw721Client<T = Option<Empty>, E = Empty, Q = Empty> {
+    pub addr: Addr,
+    extension: PhantomData<(T, E, Q)>,
+}
+
+impl<T, E, Q> Cw721Client<T, E, Q>
+where
+    T: Serialize + DeserializeOwned,
+    E: Serialize,
+    Q: Serialize,
+{
+    pub fn new(addr: Addr) -> Self {
+        Cw721Client {
+            addr,
+            extension: PhantomData,
+        }
+    }
+
+    pub fn call<C: CustomMsg>(&self, msg: ExecuteMsg<T, E>) -> StdResult<CosmosMsg<C>> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    /// same as call, with the funds such as the transfer fee attached
+    pub fn call_with_funds<C: CustomMsg>(
+        &self,
+        msg: ExecuteMsg<T, E>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg<C>> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr.to_string(),
+            msg: to_binary(&msg)?,
+            funds,
+        }
+        .into())
+    }
+
+    pub fn query<R: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: QueryMsg<Q>,
+    ) -> StdResult<R> {
+        querier.query(
+            &WasmQuery::Smart {
+                contract_addr: self.addr.to_string(),
+                msg: to_binary(&msg)?,
+            }
+            .into(),
+        )
+    }
+
+    /*** messages ***/
+
+    pub fn transfer_nft<C: CustomMsg>(
+        &self,
+        recipient: impl Into<String>,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::TransferNft {
+            recipient: recipient.into(),
+            token_id: token_id.into(),
+        })
+    }
+
+    /// msg is serialized and delivered to the contract inside Cw721ReceiveMsg
+    pub fn send_nft<C: CustomMsg, M: Serialize>(
+        &self,
+        contract: impl Into<String>,
+        token_id: impl Into<String>,
+        msg: &M,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::SendNft {
+            contract: contract.into(),
+            token_id: token_id.into(),
+            msg: to_binary(msg)?,
+        })
+    }
+
+    pub fn approve<C: CustomMsg>(
+        &self,
+        spender: impl Into<String>,
+        token_id: impl Into<String>,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Approve {
+            spender: spender.into(),
+            token_id: token_id.into(),
+            expires,
+        })
+    }
+
+    pub fn revoke<C: CustomMsg>(
+        &self,
+        spender: impl Into<String>,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Revoke {
+            spender: spender.into(),
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn approve_all<C: CustomMsg>(
+        &self,
+        operator: impl Into<String>,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::ApproveAll {
+            operator: operator.into(),
+            expires,
+        })
+    }
+
+    pub fn revoke_all<C: CustomMsg>(&self, operator: impl Into<String>) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::RevokeAll {
+            operator: operator.into(),
+        })
+    }
+
+    pub fn approve_scoped<C: CustomMsg>(
+        &self,
+        operator: impl Into<String>,
+        scope: GrantScope,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::ApproveScoped {
+            operator: operator.into(),
+            scope,
+            expires,
+        })
+    }
+
+    pub fn revoke_scoped<C: CustomMsg>(
+        &self,
+        operator: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::RevokeScoped {
+            operator: operator.into(),
+        })
+    }
+
+    pub fn mint<C: CustomMsg>(&self, msg: MintMsg<T>) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Mint(msg))
+    }
+
+    pub fn burn<C: CustomMsg>(&self, token_id: impl Into<String>) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Burn {
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn update_collection_info<C: CustomMsg>(
+        &self,
+        collection_info: CollectionInfoMsg,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdateCollectionInfo { collection_info })
+    }
+
+    /// price is attached as the funds
+    pub fn paid_mint<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+        owner: Option<String>,
+        token_uri: Option<String>,
+        extension: T,
+        price: Coin,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call_with_funds(
+            ExecuteMsg::PaidMint {
+                token_id: token_id.into(),
+                owner,
+                token_uri,
+                extension,
+            },
+            vec![price],
+        )
+    }
+
+    pub fn withdraw<C: CustomMsg>(&self) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Withdraw {})
+    }
+
+    pub fn update_native_price<C: CustomMsg>(
+        &self,
+        price: Option<Coin>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdateNativePrice { price })
+    }
+
+    pub fn update_payees<C: CustomMsg>(&self, payees: Vec<PayeeMsg>) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdatePayees { payees })
+    }
+
+    pub fn update_cw20_price<C: CustomMsg>(
+        &self,
+        token: impl Into<String>,
+        price: Option<Uint128>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdateCw20Price {
+            token: token.into(),
+            price,
+        })
+    }
+
+    pub fn update_transfer_fee<C: CustomMsg>(
+        &self,
+        transfer_fee: Option<TransferFeeMsg>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdateTransferFee { transfer_fee })
+    }
+
+    pub fn approve_lock_manager<C: CustomMsg>(
+        &self,
+        manager: impl Into<String>,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::ApproveLockManager {
+            manager: manager.into(),
+            expires,
+        })
+    }
+
+    pub fn revoke_lock_manager<C: CustomMsg>(
+        &self,
+        manager: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::RevokeLockManager {
+            manager: manager.into(),
+        })
+    }
+
+    pub fn lock_token<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::LockToken {
+            token_id: token_id.into(),
+            expires,
+        })
+    }
+
+    pub fn unlock_token<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UnlockToken {
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn update_address_list<C: CustomMsg>(
+        &self,
+        mode: Option<AddressListMode>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UpdateAddressList { mode, add, remove })
+    }
+
+    pub fn freeze_token<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::FreezeToken {
+            token_id: token_id.into(),
+            reason: reason.into(),
+        })
+    }
+
+    pub fn unfreeze_token<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::UnfreezeToken {
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn offer_transfer<C: CustomMsg>(
+        &self,
+        recipient: impl Into<String>,
+        token_id: impl Into<String>,
+        expires: Option<Expiration>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::OfferTransfer {
+            recipient: recipient.into(),
+            token_id: token_id.into(),
+            expires,
+        })
+    }
+
+    pub fn accept_transfer<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::AcceptTransfer {
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn cancel_transfer<C: CustomMsg>(
+        &self,
+        token_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::CancelTransfer {
+            token_id: token_id.into(),
+        })
+    }
+
+    pub fn migrate_tokens<C: CustomMsg>(&self, limit: Option<u32>) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::MigrateTokens { limit })
+    }
+
+    pub fn extension<C: CustomMsg>(&self, msg: E) -> StdResult<CosmosMsg<C>> {
+        self.call(ExecuteMsg::Extension { msg })
+    }
+
+    /*** queries ***/
+
+    pub fn minter(&self, querier: &QuerierWrapper) -> StdResult<String> {
+        let res: MinterResponse = self.query(querier, QueryMsg::Minter {})?;
+        Ok(res.minter)
+    }
+
+    pub fn contract_info(&self, querier: &QuerierWrapper) -> StdResult<ContractInfoResponse> {
+        self.query(querier, QueryMsg::ContractInfo {})
+    }
+
+    pub fn collection_info(&self, querier: &QuerierWrapper) -> StdResult<CollectionInfoResponse> {
+        self.query(querier, QueryMsg::CollectionInfo {})
+    }
+
+    pub fn native_price(&self, querier: &QuerierWrapper) -> StdResult<NativePriceResponse> {
+        self.query(querier, QueryMsg::NativePrice {})
+    }
+
+    pub fn payees(&self, querier: &QuerierWrapper) -> StdResult<PayeesResponse> {
+        self.query(querier, QueryMsg::Payees {})
+    }
+
+    pub fn claimable(
+        &self,
+        querier: &QuerierWrapper,
+        payee: impl Into<String>,
+    ) -> StdResult<ClaimableResponse> {
+        self.query(
+            querier,
+            QueryMsg::Claimable {
+                payee: payee.into(),
+            },
+        )
+    }
+
+    pub fn cw20_prices(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Cw20PricesResponse> {
+        self.query(querier, QueryMsg::Cw20Prices { start_after, limit })
+    }
+
+    pub fn cw20_proceeds(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Cw20ProceedsResponse> {
+        self.query(querier, QueryMsg::Cw20Proceeds { start_after, limit })
+    }
+
+    pub fn transfer_fee(&self, querier: &QuerierWrapper) -> StdResult<TransferFeeResponse> {
+        self.query(querier, QueryMsg::TransferFee {})
+    }
+
+    pub fn nft_info(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+    ) -> StdResult<NftInfoResponse<T>> {
+        self.query(
+            querier,
+            QueryMsg::NftInfo {
+                token_id: token_id.into(),
+            },
+        )
+    }
+
+    pub fn owner_of(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+        include_expired: bool,
+    ) -> StdResult<OwnerOfResponse> {
+        self.query(
+            querier,
+            QueryMsg::OwnerOf {
+                token_id: token_id.into(),
+                include_expired: Some(include_expired),
+            },
+        )
+    }
+
+    pub fn all_nft_info(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+        include_expired: bool,
+    ) -> StdResult<AllNftInfoResponse<T>> {
+        self.query(
+            querier,
+            QueryMsg::AllNftInfo {
+                token_id: token_id.into(),
+                include_expired: Some(include_expired),
+            },
+        )
+    }
+
+    pub fn operators(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Approval>> {
+        let res: OperatorsResponse = self.query(
+            querier,
+            QueryMsg::AllOperators {
+                owner: owner.into(),
+                include_expired: Some(include_expired),
+                start_after,
+                limit,
+            },
+        )?;
+        Ok(res.operators)
+    }
+
+    pub fn num_tokens(&self, querier: &QuerierWrapper) -> StdResult<u64> {
+        let res: NumTokensResponse = self.query(querier, QueryMsg::NumTokens {})?;
+        Ok(res.count)
+    }
+
+    pub fn approval(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+        spender: impl Into<String>,
+        include_expired: bool,
+    ) -> StdResult<Approval> {
+        let res: ApprovalResponse = self.query(
+            querier,
+            QueryMsg::Approval {
+                token_id: token_id.into(),
+                spender: spender.into(),
+                include_expired: Some(include_expired),
+            },
+        )?;
+        Ok(res.approval)
+    }
+
+    pub fn approvals(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+        include_expired: bool,
+    ) -> StdResult<Vec<Approval>> {
+        let res: ApprovalsResponse = self.query(
+            querier,
+            QueryMsg::Approvals {
+                token_id: token_id.into(),
+                include_expired: Some(include_expired),
+            },
+        )?;
+        Ok(res.approvals)
+    }
+
+    pub fn tokens(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        self.query(
+            querier,
+            QueryMsg::Tokens {
+                owner: owner.into(),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn all_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        self.query(querier, QueryMsg::AllTokens { start_after, limit })
+    }
+
+    pub fn burned_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        self.query(querier, QueryMsg::BurnedTokens { start_after, limit })
+    }
+
+    pub fn stats(&self, querier: &QuerierWrapper) -> StdResult<StatsResponse> {
+        self.query(querier, QueryMsg::Stats {})
+    }
+
+    pub fn token_history(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<TokenHistoryResponse> {
+        self.query(
+            querier,
+            QueryMsg::TokenHistory {
+                token_id: token_id.into(),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn locked_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<LockedTokensResponse> {
+        self.query(
+            querier,
+            QueryMsg::LockedTokens {
+                owner: owner.into(),
+                include_expired: Some(include_expired),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn address_list(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResponse> {
+        self.query(querier, QueryMsg::AddressList { start_after, limit })
+    }
+
+    pub fn frozen_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<FrozenTokensResponse> {
+        self.query(querier, QueryMsg::FrozenTokens { start_after, limit })
+    }
+
+    pub fn scoped_grant(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        operator: impl Into<String>,
+        include_expired: bool,
+    ) -> StdResult<ScopedGrantResponse> {
+        self.query(
+            querier,
+            QueryMsg::ScopedGrant {
+                owner: owner.into(),
+                operator: operator.into(),
+                include_expired: Some(include_expired),
+            },
+        )
+    }
+
+    pub fn scoped_grants(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ScopedGrantsResponse> {
+        self.query(
+            querier,
+            QueryMsg::ScopedGrants {
+                owner: owner.into(),
+                include_expired: Some(include_expired),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn pending_transfer(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: impl Into<String>,
+    ) -> StdResult<PendingTransferResponse> {
+        self.query(
+            querier,
+            QueryMsg::PendingTransfer {
+                token_id: token_id.into(),
+            },
+        )
+    }
+
+    pub fn pending_transfers(
+        &self,
+        querier: &QuerierWrapper,
+        recipient: impl Into<String>,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<PendingTransfersResponse> {
+        self.query(
+            querier,
+            QueryMsg::PendingTransfers {
+                recipient: recipient.into(),
+                include_expired: Some(include_expired),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn query_extension<R: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: Q,
+    ) -> StdResult<R> {
+        self.query(querier, QueryMsg::Extension { msg })
+    }
+}
+
+#[cfg(test)]
+mod client_tests {
+    use crate::client::Cw721Client;
+    use crate::msg::{CollectionInfoMsg, ExecuteMsg, MintMsg, PayeeMsg, TransferFeeMsg};
+    use crate::state::{AddressListMode, GrantScope};
+    use crate::testing::MockCw721Querier;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{
+        coin, from_binary, to_binary, Addr, Coin, CosmosMsg, Empty, QuerierWrapper, StdResult,
+        Uint128, WasmMsg,
+    };
+    use cw721::{Expiration, NftInfoResponse};
+
+    const COLLECTION: &str = "collection";
+    const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+    const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+
+    type Extension = Option<String>;
+    /// built message, the ExecuteMsg it must carry and the funds attached to it
+    type MessageCase = (
+        StdResult<CosmosMsg>,
+        ExecuteMsg<Extension, Empty>,
+        Vec<Coin>,
+    );
+
+    fn client() -> Cw721Client<Extension> {
+        Cw721Client::new(Addr::unchecked(COLLECTION))
+    }
+
+    #[test]
+    fn test_messages() {
+        let msg: CosmosMsg = client()
+            .approve(ADDR2, "1", Some(Expiration::Never {}))
+            .unwrap();
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COLLECTION.to_string(),
+                msg: to_binary(&ExecuteMsg::<Extension, Empty>::Approve {
+                    spender: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                    expires: Some(Expiration::Never {}),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // the embedded message is serialized into SendNft as is
+        let msg: CosmosMsg = client().send_nft(ADDR2, "1", &"hello").unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(&msg).unwrap() {
+                ExecuteMsg::<Extension, Empty>::SendNft { msg, .. } => {
+                    assert_eq!(msg, to_binary("hello").unwrap())
+                }
+                msg => panic!("unexpected message: {:?}", msg),
+            },
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn test_every_message() {
+        let client = client();
+        let price = coin(100, "ujuno");
+        let mint_msg = MintMsg {
+            token_id: "1".to_string(),
+            owner: ADDR1.to_string(),
+            token_uri: Some("ipfs://1".to_string()),
+            extension: Some("metadata".to_string()),
+        };
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: ADDR2.to_string(),
+            token_id: "1".to_string(),
+        };
+        let transfer_fee = TransferFeeMsg {
+            amount: price.clone(),
+            treasury: ADDR2.to_string(),
+        };
+        let scope = GrantScope::Prefix {
+            prefix: "season-1".to_string(),
+        };
+        let payees = vec![PayeeMsg {
+            address: ADDR2.to_string(),
+            shares_bps: 10_000,
+        }];
+
+        let cases: Vec<MessageCase> = vec![
+            (client.transfer_nft(ADDR2, "1"), transfer.clone(), vec![]),
+            (
+                client.call_with_funds(transfer.clone(), vec![price.clone()]),
+                transfer,
+                vec![price.clone()],
+            ),
+            (
+                client.send_nft(ADDR2, "1", &"hello"),
+                ExecuteMsg::SendNft {
+                    contract: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                    msg: to_binary("hello").unwrap(),
+                },
+                vec![],
+            ),
+            (
+                client.approve(ADDR2, "1", Some(Expiration::Never {})),
+                ExecuteMsg::Approve {
+                    spender: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                    expires: Some(Expiration::Never {}),
+                },
+                vec![],
+            ),
+            (
+                client.revoke(ADDR2, "1"),
+                ExecuteMsg::Revoke {
+                    spender: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.approve_all(ADDR2, None),
+                ExecuteMsg::ApproveAll {
+                    operator: ADDR2.to_string(),
+                    expires: None,
+                },
+                vec![],
+            ),
+            (
+                client.revoke_all(ADDR2),
+                ExecuteMsg::RevokeAll {
+                    operator: ADDR2.to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.approve_scoped(ADDR2, scope.clone(), Some(Expiration::AtHeight(100))),
+                ExecuteMsg::ApproveScoped {
+                    operator: ADDR2.to_string(),
+                    scope,
+                    expires: Some(Expiration::AtHeight(100)),
+                },
+                vec![],
+            ),
+            (
+                client.revoke_scoped(ADDR2),
+                ExecuteMsg::RevokeScoped {
+                    operator: ADDR2.to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.mint(mint_msg.clone()),
+                ExecuteMsg::Mint(mint_msg.clone()),
+                vec![],
+            ),
+            (
+                client.burn("1"),
+                ExecuteMsg::Burn {
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.update_collection_info(CollectionInfoMsg::default()),
+                ExecuteMsg::UpdateCollectionInfo {
+                    collection_info: CollectionInfoMsg::default(),
+                },
+                vec![],
+            ),
+            (
+                client.paid_mint(
+                    "1",
+                    Some(ADDR1.to_string()),
+                    mint_msg.token_uri.clone(),
+                    mint_msg.extension.clone(),
+                    price.clone(),
+                ),
+                ExecuteMsg::PaidMint {
+                    token_id: "1".to_string(),
+                    owner: Some(ADDR1.to_string()),
+                    token_uri: mint_msg.token_uri,
+                    extension: mint_msg.extension,
+                },
+                vec![price.clone()],
+            ),
+            (client.withdraw(), ExecuteMsg::Withdraw {}, vec![]),
+            (
+                client.update_native_price(Some(price.clone())),
+                ExecuteMsg::UpdateNativePrice { price: Some(price) },
+                vec![],
+            ),
+            (
+                client.update_payees(payees.clone()),
+                ExecuteMsg::UpdatePayees { payees },
+                vec![],
+            ),
+            (
+                client.update_cw20_price("cw20", Some(Uint128::new(5))),
+                ExecuteMsg::UpdateCw20Price {
+                    token: "cw20".to_string(),
+                    price: Some(Uint128::new(5)),
+                },
+                vec![],
+            ),
+            (
+                client.update_transfer_fee(Some(transfer_fee.clone())),
+                ExecuteMsg::UpdateTransferFee {
+                    transfer_fee: Some(transfer_fee),
+                },
+                vec![],
+            ),
+            (
+                client.approve_lock_manager(ADDR2, None),
+                ExecuteMsg::ApproveLockManager {
+                    manager: ADDR2.to_string(),
+                    expires: None,
+                },
+                vec![],
+            ),
+            (
+                client.revoke_lock_manager(ADDR2),
+                ExecuteMsg::RevokeLockManager {
+                    manager: ADDR2.to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.lock_token("1", Some(Expiration::AtHeight(100))),
+                ExecuteMsg::LockToken {
+                    token_id: "1".to_string(),
+                    expires: Some(Expiration::AtHeight(100)),
+                },
+                vec![],
+            ),
+            (
+                client.unlock_token("1"),
+                ExecuteMsg::UnlockToken {
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.update_address_list(
+                    Some(AddressListMode::Allowlist),
+                    vec![ADDR1.to_string()],
+                    vec![ADDR2.to_string()],
+                ),
+                ExecuteMsg::UpdateAddressList {
+                    mode: Some(AddressListMode::Allowlist),
+                    add: vec![ADDR1.to_string()],
+                    remove: vec![ADDR2.to_string()],
+                },
+                vec![],
+            ),
+            (
+                client.freeze_token("1", "dispute"),
+                ExecuteMsg::FreezeToken {
+                    token_id: "1".to_string(),
+                    reason: "dispute".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.unfreeze_token("1"),
+                ExecuteMsg::UnfreezeToken {
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.offer_transfer(ADDR2, "1", None),
+                ExecuteMsg::OfferTransfer {
+                    recipient: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                    expires: None,
+                },
+                vec![],
+            ),
+            (
+                client.accept_transfer("1"),
+                ExecuteMsg::AcceptTransfer {
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.cancel_transfer("1"),
+                ExecuteMsg::CancelTransfer {
+                    token_id: "1".to_string(),
+                },
+                vec![],
+            ),
+            (
+                client.migrate_tokens(Some(50)),
+                ExecuteMsg::MigrateTokens { limit: Some(50) },
+                vec![],
+            ),
+            (
+                client.extension(Empty {}),
+                ExecuteMsg::Extension { msg: Empty {} },
+                vec![],
+            ),
+        ];
+
+        for (msg, expected, expected_funds) in cases {
+            match msg.unwrap() {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr,
+                    msg,
+                    funds,
+                }) => {
+                    assert_eq!(contract_addr, COLLECTION);
+                    assert_eq!(
+                        from_binary::<ExecuteMsg<Extension, Empty>>(&msg).unwrap(),
+                        expected
+                    );
+                    assert_eq!(funds, expected_funds, "funds of {:?}", expected);
+                }
+                msg => panic!("unexpected message: {:?}", msg),
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let collection = MockCw721Querier::<Extension>::new(COLLECTION, ADDR1);
+        collection.mint("1", ADDR1, None, Some("metadata".to_string()));
+        collection.approve("1", ADDR2, Expiration::Never {});
+
+        let mut querier = MockQuerier::<Empty>::new(&[]);
+        collection.install(&mut querier);
+        let querier = QuerierWrapper::new(&querier);
+
+        let client = client();
+        assert_eq!(client.minter(&querier).unwrap(), ADDR1);
+        assert_eq!(client.contract_info(&querier).unwrap().name, COLLECTION);
+        assert_eq!(client.collection_info(&querier).unwrap().admin, ADDR1);
+        assert_eq!(client.num_tokens(&querier).unwrap(), 1);
+        assert_eq!(
+            client.nft_info(&querier, "1").unwrap(),
+            NftInfoResponse {
+                token_uri: None,
+                extension: Some("metadata".to_string()),
+            }
+        );
+        assert_eq!(client.owner_of(&querier, "1", false).unwrap().owner, ADDR1);
+        assert_eq!(
+            client
+                .approval(&querier, "1", ADDR2, false)
+                .unwrap()
+                .spender,
+            ADDR2
+        );
+        assert_eq!(client.approvals(&querier, "1", false).unwrap().len(), 1);
+        assert_eq!(
+            client
+                .all_nft_info(&querier, "1", false)
+                .unwrap()
+                .info
+                .extension,
+            Some("metadata".to_string())
+        );
+        #[cfg(not(feature = "non-enumerable"))]
+        {
+            assert_eq!(
+                client.tokens(&querier, ADDR1, None, None).unwrap().tokens,
+                vec!["1".to_string()]
+            );
+            assert_eq!(
+                client.all_tokens(&querier, None, None).unwrap().tokens,
+                vec!["1".to_string()]
+            );
+        }
+        assert_eq!(
+            client
+                .operators(&querier, ADDR1, false, None, None)
+                .unwrap(),
+            vec![]
+        );
+        client.owner_of(&querier, "2", false).unwrap_err();
+    }
+}
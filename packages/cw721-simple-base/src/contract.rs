@@ -1,13 +1,18 @@
 use crate::error::ContractError;
-use crate::state::{set_contract_info, set_minter};
+use crate::execute::{_mint, validate_collection_info, validate_transfer_fee};
+use crate::extension::Cw721Extension;
+use crate::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, QueryMsg};
+use crate::state::{
+    load_pending_transfer, migrate_token_batch, migration_pending, set_contract_info, set_minter,
+    Migration, ADMIN, ALLOW_REMINT, COLLECTION_INFO, DEFAULT_MIGRATION_LIMIT, ENUMERABLE,
+    ENUMERABLE_BUILD, HISTORY_CAP, MIGRATION, TOKEN_ID_POLICY, TRANSFER_FEE,
+};
 use crate::{execute, query};
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    from_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult,
+};
 use cw2::set_contract_version;
 use cw721::{ContractInfoResponse, CustomMsg};
-use cw721_base::msg::ExecuteMsg;
-use cw721_base::{InstantiateMsg, QueryMsg};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::error::Error;
@@ -16,13 +21,17 @@ use std::fmt::Debug;
 const CONTRACT_NAME: &str = "crates.io:cw721-simple-base";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
+pub fn instantiate<T, C, E>(
+    mut deps: DepsMut,
+    env: Env,
     _info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
+    msg: InstantiateMsg<T>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: Debug + PartialEq + Error,
+{
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let contract_info = ContractInfoResponse {
@@ -31,74 +40,313 @@ pub fn instantiate(
     };
     let minter = deps.api.addr_validate(&msg.minter)?;
 
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => minter.clone(),
+    };
+
     set_contract_info(deps.storage, contract_info)?;
     set_minter(deps.storage, minter)?;
+    ADMIN.save(deps.storage, &admin)?;
+    if let Some(collection_info) = msg.collection_info {
+        let collection_info = validate_collection_info(deps.api, collection_info)?;
+        COLLECTION_INFO.save(deps.storage, &collection_info)?;
+    }
+    if let Some(token_id_policy) = msg.token_id_policy {
+        TOKEN_ID_POLICY.save(deps.storage, &token_id_policy)?;
+    }
+    ALLOW_REMINT.save(deps.storage, &msg.allow_remint)?;
+    ENUMERABLE.save(deps.storage, &ENUMERABLE_BUILD)?;
+    // new collections have no legacy tokens to migrate
+    MIGRATION.save(
+        deps.storage,
+        &Migration {
+            last_token: None,
+            done: true,
+            seed_stats: false,
+        },
+    )?;
+    if let Some(history_cap) = msg.history_cap {
+        HISTORY_CAP.save(deps.storage, &history_cap)?;
+    }
+    if let Some(transfer_fee) = msg.transfer_fee {
+        let transfer_fee = validate_transfer_fee(deps.api, transfer_fee)?;
+        TRANSFER_FEE.save(deps.storage, &transfer_fee)?;
+    }
+
+    // genesis mints go through the checks of mint, the extension hooks are not called
+    let mut res = Response::default();
+    for mut mint in msg.initial_mints {
+        mint.token_id = execute::fold_token_id(deps.storage, &mint.token_id)?;
+        let owner = mint.owner.clone();
+        let token_id = _mint(deps.branch(), &env, mint)?;
+        res = res.add_event(
+            Event::new("genesis_mint")
+                .add_attribute("owner", owner)
+                .add_attribute("token_id", token_id),
+        );
+    }
 
-    Ok(Response::default())
+    Ok(res)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute<T, M, C, E>(
+/// Upgrades collections deployed with cw721-base or an earlier version,
+/// token records that still hold their metadata are split into TokenInfo and TokenMetadata
+/// and the stats are seeded from the live tokens when they were not tracked yet.
+/// Tokens go through in batches of `limit`, the admin runs the next ones with MigrateTokens.
+/// Executes are paused and the seeded stats are not queryable until the last one.
+/// Fails when the build doesn't match the enumerable mode of the collection
+pub fn migrate<T, C, E>(
     deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg<T, M>,
+    _env: Env,
+    msg: MigrateMsg,
 ) -> Result<Response<C>, ContractError<E>>
 where
     T: Serialize + DeserializeOwned + Clone,
-    // Custom message for extension M have to implement traits same as T
-    M: Serialize + DeserializeOwned + Clone,
-    E: Debug + PartialEq + Error,
     C: CustomMsg,
+    E: Debug + PartialEq + Error,
 {
+    // builds with and without the owner index can't take over each other's collections
+    let enumerable = ENUMERABLE.may_load(deps.storage)?.unwrap_or(true);
+    if enumerable != ENUMERABLE_BUILD {
+        return Err(ContractError::EnumerableChanged { enumerable });
+    }
+    ENUMERABLE.save(deps.storage, &enumerable)?;
+
+    let limit = msg.limit.unwrap_or(DEFAULT_MIGRATION_LIMIT);
+    let migrated = migrate_token_batch::<T>(deps.storage, limit)?;
+    let done = !migration_pending(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_tokens", migrated.to_string())
+        .add_attribute("migration_done", done.to_string()))
+}
+
+pub fn execute<X>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg<X::Extension, X::ExecuteMsg>,
+    extension: &X,
+) -> Result<Response<X::CustomMsg>, ContractError<X::Error>>
+where
+    X: Cw721Extension,
+{
+    // token records still change shape while a migration is pending
+    if !matches!(msg, ExecuteMsg::MigrateTokens { .. }) && migration_pending(deps.storage)? {
+        return Err(ContractError::MigrationPending {});
+    }
+
+    let mut msg = msg;
+    if let Some(token_id) = msg.token_id_mut() {
+        *token_id = execute::fold_token_id(deps.storage, token_id)?;
+    }
+
     match msg {
-        ExecuteMsg::Mint(msg) => execute::mint::<T, C, E>(deps, env, info, msg),
+        ExecuteMsg::Mint(mut msg) => {
+            // hooks see the token_id that is minted, it is validated after the minter is checked
+            msg.token_id = execute::fold_token_id(deps.storage, &msg.token_id)?;
+            extension.before_mint(deps.as_ref(), &env, &info, &msg)?;
+            let token_id = msg.token_id.clone();
+            let res = execute::mint(deps.branch(), env.clone(), info.clone(), msg)?;
+            extension.after_mint(deps, &env, &info, &token_id, res)
+        }
         ExecuteMsg::Approve {
             spender,
             token_id,
             expires,
-        } => execute::approve::<T, C, E>(deps, env, info, spender, token_id, expires),
+        } => execute::approve(deps, env, info, spender, token_id, expires),
         ExecuteMsg::Revoke { spender, token_id } => {
-            execute::revoke::<T, C, E>(deps, env, info, spender, token_id)
+            execute::revoke(deps, env, info, spender, token_id)
         }
         ExecuteMsg::ApproveAll { operator, expires } => {
-            execute::approve_all::<C, E>(deps, env, info, operator, expires)
-        }
-        ExecuteMsg::RevokeAll { operator } => {
-            execute::revoke_all::<C, E>(deps, env, info, operator)
+            execute::approve_all(deps, env, info, operator, expires)
         }
+        ExecuteMsg::RevokeAll { operator } => execute::revoke_all(deps, env, info, operator),
+        ExecuteMsg::ApproveScoped {
+            operator,
+            scope,
+            expires,
+        } => execute::approve_scoped(deps, env, info, operator, scope, expires),
+        ExecuteMsg::RevokeScoped { operator } => execute::revoke_scoped(deps, env, info, operator),
         ExecuteMsg::TransferNft {
             recipient,
             token_id,
-        } => execute::transfer_nft::<T, C, E>(deps, env, info, recipient, token_id),
+        } => {
+            extension.before_transfer(deps.as_ref(), &env, &info, &token_id, &recipient)?;
+            let res = execute::transfer_nft(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                recipient.clone(),
+                token_id.clone(),
+            )?;
+            extension.after_transfer(deps, &env, &info, &token_id, &recipient, res)
+        }
         ExecuteMsg::SendNft {
             contract,
             token_id,
             msg,
-        } => execute::send_nft::<T, C, E>(deps, env, info, contract, token_id, msg),
-        ExecuteMsg::Burn { token_id } => execute::burn::<T, C, E>(deps, env, info, token_id),
-        ExecuteMsg::Extension { msg: _ } => Ok(Response::new()),
+        } => {
+            extension.before_transfer(deps.as_ref(), &env, &info, &token_id, &contract)?;
+            let res = execute::send_nft(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                contract.clone(),
+                token_id.clone(),
+                msg,
+            )?;
+            extension.after_transfer(deps, &env, &info, &token_id, &contract, res)
+        }
+        ExecuteMsg::Burn { token_id } => {
+            extension.before_burn(deps.as_ref(), &env, &info, &token_id)?;
+            let res = execute::burn::<X::Extension, _, _>(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                token_id.clone(),
+            )?;
+            extension.after_burn(deps, &env, &info, &token_id, res)
+        }
+        ExecuteMsg::UpdateCollectionInfo { collection_info } => {
+            execute::update_collection_info(deps, env, info, collection_info)
+        }
+        ExecuteMsg::Receive(receive) => {
+            // the cw20 transfer pays for the mint embedded in it
+            match from_binary(&receive.msg)? {
+                Cw20HookMsg::Mint {
+                    token_id,
+                    owner,
+                    token_uri,
+                    extension: token_extension,
+                } => {
+                    let msg = MintMsg {
+                        token_id: execute::fold_token_id(deps.storage, &token_id)?,
+                        owner: owner.unwrap_or_else(|| receive.sender.clone()),
+                        token_uri,
+                        extension: token_extension,
+                    };
+                    // hooks see the mint as sent by the buyer, not by the cw20 contract
+                    let buyer_info = MessageInfo {
+                        sender: deps.api.addr_validate(&receive.sender)?,
+                        funds: vec![],
+                    };
+                    extension.before_mint(deps.as_ref(), &env, &buyer_info, &msg)?;
+                    let token_id = msg.token_id.clone();
+                    let res =
+                        execute::cw20_mint(deps.branch(), env.clone(), info, receive.amount, msg)?;
+                    extension.after_mint(deps, &env, &buyer_info, &token_id, res)
+                }
+            }
+        }
+        ExecuteMsg::PaidMint {
+            token_id,
+            owner,
+            token_uri,
+            extension: token_extension,
+        } => {
+            let msg = MintMsg {
+                token_id,
+                owner: owner.unwrap_or_else(|| info.sender.to_string()),
+                token_uri,
+                extension: token_extension,
+            };
+            extension.before_mint(deps.as_ref(), &env, &info, &msg)?;
+            let token_id = msg.token_id.clone();
+            let res = execute::paid_mint(deps.branch(), env.clone(), info.clone(), msg)?;
+            extension.after_mint(deps, &env, &info, &token_id, res)
+        }
+        ExecuteMsg::Withdraw {} => execute::withdraw(deps, env, info),
+        ExecuteMsg::UpdateNativePrice { price } => {
+            execute::update_native_price(deps, env, info, price)
+        }
+        ExecuteMsg::UpdatePayees { payees } => execute::update_payees(deps, env, info, payees),
+        ExecuteMsg::UpdateCw20Price { token, price } => {
+            execute::update_cw20_price(deps, env, info, token, price)
+        }
+        ExecuteMsg::UpdateTransferFee { transfer_fee } => {
+            execute::update_transfer_fee(deps, env, info, transfer_fee)
+        }
+        ExecuteMsg::ApproveLockManager { manager, expires } => {
+            execute::approve_lock_manager(deps, env, info, manager, expires)
+        }
+        ExecuteMsg::RevokeLockManager { manager } => {
+            execute::revoke_lock_manager(deps, env, info, manager)
+        }
+        ExecuteMsg::LockToken { token_id, expires } => {
+            execute::lock_token(deps, env, info, token_id, expires)
+        }
+        ExecuteMsg::UnlockToken { token_id } => execute::unlock_token(deps, env, info, token_id),
+        ExecuteMsg::UpdateAddressList { mode, add, remove } => {
+            execute::update_address_list(deps, env, info, mode, add, remove)
+        }
+        ExecuteMsg::FreezeToken { token_id, reason } => {
+            execute::freeze_token(deps, env, info, token_id, reason)
+        }
+        ExecuteMsg::UnfreezeToken { token_id } => {
+            execute::unfreeze_token(deps, env, info, token_id)
+        }
+        ExecuteMsg::OfferTransfer {
+            recipient,
+            token_id,
+            expires,
+        } => execute::offer_transfer(deps, env, info, recipient, token_id, expires),
+        ExecuteMsg::AcceptTransfer { token_id } => {
+            // hooks see the transfer as sent by the sender of the offer
+            let pending = load_pending_transfer(deps.storage, &token_id)?;
+            let sender_info = MessageInfo {
+                sender: pending.sender,
+                funds: vec![],
+            };
+            let recipient = pending.recipient.into_string();
+            extension.before_transfer(deps.as_ref(), &env, &sender_info, &token_id, &recipient)?;
+            let res = execute::accept_transfer(deps.branch(), env.clone(), info, token_id.clone())?;
+            extension.after_transfer(deps, &env, &sender_info, &token_id, &recipient, res)
+        }
+        ExecuteMsg::CancelTransfer { token_id } => {
+            execute::cancel_transfer(deps, env, info, token_id)
+        }
+        ExecuteMsg::MigrateTokens { limit } => {
+            execute::migrate_tokens::<X::Extension, _, _>(deps, env, info, limit)
+        }
+        ExecuteMsg::Extension { msg } => extension.execute_extension(deps, env, info, msg),
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query<T, Q>(deps: Deps, env: Env, msg: QueryMsg<Q>) -> StdResult<Binary>
+pub fn query<X>(
+    deps: Deps,
+    env: Env,
+    msg: QueryMsg<X::QueryMsg>,
+    extension: &X,
+) -> StdResult<Binary>
 where
-    T: Serialize + DeserializeOwned + Clone,
-    Q: Serialize + DeserializeOwned + Clone,
+    X: Cw721Extension,
 {
+    let mut msg = msg;
+    if let Some(token_id) = msg.token_id_mut() {
+        *token_id = execute::fold_token_id(deps.storage, token_id)?;
+    }
+
     match msg {
         QueryMsg::Minter {} => query::minter(deps),
         QueryMsg::ContractInfo {} => query::contract_info(deps),
-        QueryMsg::NftInfo { token_id } => query::nft_info::<T>(deps, token_id),
+        QueryMsg::NftInfo { token_id } => query::nft_info::<X::Extension>(deps, token_id),
         QueryMsg::OwnerOf {
             token_id,
             include_expired,
-        } => query::owner_of::<T>(deps, env, token_id, include_expired.unwrap_or(false)),
+        } => query::owner_of(deps, env, token_id, include_expired.unwrap_or(false)),
         QueryMsg::AllNftInfo {
             token_id,
             include_expired,
-        } => query::all_nft_info::<T>(deps, env, token_id, include_expired.unwrap_or(false)),
+        } => query::all_nft_info::<X::Extension>(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        ),
         QueryMsg::AllOperators {
             owner,
             include_expired,
@@ -113,14 +361,12 @@ where
             limit,
         ),
         QueryMsg::NumTokens {} => query::num_tokens(deps),
-        QueryMsg::AllTokens { start_after, limit } => {
-            query::all_tokens::<T>(deps, start_after, limit)
-        }
+        QueryMsg::AllTokens { start_after, limit } => query::all_tokens(deps, start_after, limit),
         QueryMsg::Approval {
             token_id,
             spender,
             include_expired,
-        } => query::approval::<T>(
+        } => query::approval(
             deps,
             env,
             token_id,
@@ -130,556 +376,486 @@ where
         QueryMsg::Approvals {
             token_id,
             include_expired,
-        } => query::approvals::<T>(deps, env, token_id, include_expired.unwrap_or(false)),
+        } => query::approvals(deps, env, token_id, include_expired.unwrap_or(false)),
         QueryMsg::Tokens {
             owner,
             start_after,
             limit,
-        } => query::tokens::<T>(deps, owner, start_after, limit),
-        QueryMsg::Extension { msg: _ } => Ok(Binary::default()),
+        } => query::tokens(deps, owner, start_after, limit),
+        QueryMsg::CollectionInfo {} => query::collection_info(deps),
+        QueryMsg::BurnedTokens { start_after, limit } => {
+            query::burned_tokens(deps, start_after, limit)
+        }
+        QueryMsg::NativePrice {} => query::native_price(deps),
+        QueryMsg::Payees {} => query::payees(deps),
+        QueryMsg::Claimable { payee } => query::claimable(deps, payee),
+        QueryMsg::Cw20Prices { start_after, limit } => query::cw20_prices(deps, start_after, limit),
+        QueryMsg::Cw20Proceeds { start_after, limit } => {
+            query::cw20_proceeds(deps, start_after, limit)
+        }
+        QueryMsg::TransferFee {} => query::transfer_fee(deps),
+        QueryMsg::Stats {} => query::stats(deps),
+        QueryMsg::LockedTokens {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => query::locked_tokens(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        ),
+        QueryMsg::AddressList { start_after, limit } => {
+            query::address_list(deps, start_after, limit)
+        }
+        QueryMsg::FrozenTokens { start_after, limit } => {
+            query::frozen_tokens(deps, start_after, limit)
+        }
+        QueryMsg::ScopedGrant {
+            owner,
+            operator,
+            include_expired,
+        } => query::scoped_grant(deps, env, owner, operator, include_expired.unwrap_or(false)),
+        QueryMsg::ScopedGrants {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => query::scoped_grants(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        ),
+        QueryMsg::PendingTransfer { token_id } => query::pending_transfer(deps, token_id),
+        QueryMsg::PendingTransfers {
+            recipient,
+            include_expired,
+            start_after,
+            limit,
+        } => query::pending_transfers(
+            deps,
+            env,
+            recipient,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        ),
+        QueryMsg::TokenHistory {
+            token_id,
+            start_after,
+            limit,
+        } => query::token_history(deps, token_id, start_after, limit),
+        QueryMsg::Extension { msg } => extension.query_extension(deps, env, msg),
     }
 }
 
 #[cfg(test)]
 pub mod contract_tests {
-    use crate::contract::{execute, instantiate, query};
+    use crate::contract::{execute, instantiate, migrate, query};
     use crate::error::{ContractError, CustomError};
-    use crate::state::{get_tokens, TokenInfo};
+    use crate::extension::{Cw721Extension, UNSUPPORTED_EXTENSION_QUERY};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, QueryMsg, StatsResponse};
+    use crate::state::{
+        get_token_metadata, get_tokens, LegacyTokenInfo, TokenIdPolicy, TokenMetadata, ENUMERABLE,
+        ENUMERABLE_BUILD, MIGRATION,
+    };
+    use crate::testing::fixtures::{init, mint, Extension, TestExtension, ADDR1, ADDR2};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary, to_binary, DepsMut, Empty, Response};
-    use cw721::{
-        AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
-        Expiration, NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
-        TokensResponse,
+    use cosmwasm_std::{
+        attr, from_binary, to_binary, Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo,
+        Response, StdError,
     };
-    use cw721_base::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
-
-    const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
-    const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+    use cw721::{NftInfoResponse, NumTokensResponse};
+    use cw_storage_plus::Map;
 
-    type Extension = Option<Empty>;
+    // the suite pages through Tokens and AllTokens
+    #[cfg(not(feature = "non-enumerable"))]
+    #[test]
+    fn test_conformance() {
+        use cw721_simple_conformance::Cw721Suite;
 
-    fn init(deps: DepsMut) {
-        instantiate(
-            deps,
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            InstantiateMsg {
-                name: "cw721-contract".to_string(),
-                symbol: "cw721".to_string(),
-                minter: ADDR1.to_string(),
+        Cw721Suite {
+            instantiate: instantiate::<Extension, Empty, CustomError>,
+            execute: |deps, env, info, msg: ExecuteMsg<Extension, Empty>| {
+                execute(deps, env, info, msg, &TestExtension)
             },
-        )
-        .unwrap();
+            query: |deps, env, msg: QueryMsg<Empty>| query(deps, env, msg, &TestExtension),
+            extensions: vec![None, Some(Empty {})],
+        }
+        .run_all();
     }
 
-    fn mint(deps: DepsMut, owner: &str, token_id: &str) -> Result<Response, ContractError> {
-        let execute_mint_msg = ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
+    #[test]
+    fn test_genesis_mints() {
+        let genesis = |initial_mints: Vec<MintMsg<Extension>>| {
+            let mut deps = mock_dependencies();
+            let res = instantiate::<Extension, Empty, CustomError>(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADDR2, &[]),
+                InstantiateMsg {
+                    minter: ADDR1.to_string(),
+                    token_id_policy: Some(TokenIdPolicy {
+                        numeric_only: true,
+                        ..TokenIdPolicy::default()
+                    }),
+                    initial_mints,
+                    ..Default::default()
+                },
+            );
+            (deps, res)
+        };
+        let mint_msg = |token_id: &str, owner: &str| MintMsg {
             token_id: token_id.to_string(),
             owner: owner.to_string(),
-            token_uri: None,
-            extension: None,
-        });
-
-        execute::<Extension, Empty, Empty, CustomError>(
-            deps,
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            execute_mint_msg,
-        )
-    }
-
-    fn approve(deps: DepsMut, sender: &str, spender: &str) -> Result<Response, ContractError> {
-        let valid_approve_msg = ExecuteMsg::Approve {
-            spender: spender.to_string(),
-            token_id: "1".to_string(),
-            expires: Some(Expiration::AtHeight(50000)),
-        };
-
-        execute::<Extension, Empty, Empty, CustomError>(
-            deps,
-            mock_env(),
-            mock_info(sender, &[]),
-            valid_approve_msg,
-        )
-    }
-
-    fn transfer_nft(
-        deps: DepsMut,
-        sender: &str,
-        recipient: &str,
-    ) -> Result<Response, ContractError> {
-        let transfer_nft_msg = ExecuteMsg::<Extension, Empty>::TransferNft {
-            recipient: recipient.to_string(),
-            token_id: "1".to_string(),
+            token_uri: Some(format!("ipfs://{}", token_id)),
+            extension: Some(Empty {}),
         };
 
-        execute::<Extension, Empty, Empty, CustomError>(
-            deps,
-            mock_env(),
-            mock_info(sender, &[]),
-            transfer_nft_msg,
-        )
-    }
-
-    #[test]
-    fn test_execute_mint() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        let res = mint(deps.as_mut(), ADDR1, "1").unwrap();
-        mint(deps.as_mut(), ADDR1, "2").unwrap();
-
-        let token_1: TokenInfo<Extension> = get_tokens().load(&deps.storage, "1").unwrap();
-
-        assert_eq!(token_1.owner, ADDR1);
-        assert_eq!(token_1.extension, None);
+        let (deps, res) = genesis(vec![mint_msg("1", ADDR1), mint_msg("2", ADDR2)]);
+        let res = res.unwrap();
         assert_eq!(
-            res.attributes,
-            [
-                attr("action", "mint"),
-                attr("minter", ADDR1),
-                attr("owner", ADDR1),
-                attr("token_id", "1"),
+            res.events,
+            vec![
+                Event::new("genesis_mint")
+                    .add_attribute("owner", ADDR1)
+                    .add_attribute("token_id", "1"),
+                Event::new("genesis_mint")
+                    .add_attribute("owner", ADDR2)
+                    .add_attribute("token_id", "2"),
             ]
         );
-
-        let num_tokens_query_msg = QueryMsg::<Empty>::NumTokens {};
-        let num_tokens_query_res: NumTokensResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), num_tokens_query_msg).unwrap(),
+        assert_eq!(get_tokens().load(&deps.storage, "2").unwrap().owner, ADDR2);
+        let metadata: TokenMetadata<Extension> =
+            get_token_metadata().load(&deps.storage, "1").unwrap();
+        assert_eq!(metadata.token_uri, Some("ipfs://1".to_string()));
+        let num_tokens: NumTokensResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NumTokens {},
+                &TestExtension,
+            )
+            .unwrap(),
         )
         .unwrap();
-        assert_eq!(num_tokens_query_res, NumTokensResponse { count: 2 });
-    }
-
-    #[test]
-    fn test_approve() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        let valid_res = approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-
-        assert_eq!(
-            valid_res.attributes,
-            [
-                attr("action", "approve"),
-                attr("sender", ADDR1),
-                attr("spender", ADDR2),
-                attr("token_id", "1"),
-            ]
-        );
-
-        let query_approval_msg = QueryMsg::<Empty>::Approval {
-            token_id: "1".to_string(),
-            spender: ADDR2.to_string(),
-            include_expired: None,
-        };
+        assert_eq!(num_tokens.count, 2);
 
-        let query_approval_res: ApprovalResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), query_approval_msg).unwrap(),
-        )
-        .unwrap();
+        // same checks as mint
+        let (_, res) = genesis(vec![mint_msg("1", ADDR1), mint_msg("1", ADDR2)]);
+        assert_eq!(res.unwrap_err(), ContractError::Claimed {});
+        let (_, res) = genesis(vec![mint_msg("01", ADDR1)]);
         assert_eq!(
-            query_approval_res,
-            ApprovalResponse {
-                approval: Approval {
-                    spender: ADDR2.to_string(),
-                    expires: Expiration::AtHeight(50000),
-                }
+            res.unwrap_err(),
+            ContractError::TokenIdNotNumeric {
+                token_id: "01".to_string()
             }
         );
+        let (_, res) = genesis(vec![mint_msg("1", "")]);
+        assert_eq!(res.unwrap_err().code(), "std");
 
-        let query_approvals_msg = QueryMsg::<Empty>::Approvals {
-            token_id: "1".to_string(),
-            include_expired: Some(true),
-        };
-
-        let approvals_res: ApprovalsResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), query_approvals_msg).unwrap(),
+        // the JSON of a cw721-base InstantiateMsg has no genesis mints
+        let msg: InstantiateMsg<Extension> = from_binary(
+            &to_binary(&cw721_base::InstantiateMsg {
+                name: "cw721-contract".to_string(),
+                symbol: "cw721".to_string(),
+                minter: ADDR1.to_string(),
+            })
+            .unwrap(),
         )
         .unwrap();
-        assert_eq!(
-            approvals_res,
-            ApprovalsResponse {
-                approvals: vec![Approval {
-                    spender: ADDR2.to_string(),
-                    expires: Expiration::AtHeight(50000),
-                }]
-            }
-        );
-
-        let expired_approve_msg = ExecuteMsg::<Extension, Empty>::Approve {
-            spender: ADDR2.to_string(),
-            token_id: "1".to_string(),
-            expires: Some(Expiration::AtHeight(100)),
-        };
-
-        let invalid_res = execute::<Extension, Empty, Empty, CustomError>(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            expired_approve_msg,
-        )
-        .unwrap_err();
-        assert_eq!(invalid_res, ContractError::Expired {});
-
-        // Unauthorized approve
-        let unauthorized_res = approve(deps.as_mut(), ADDR2, ADDR2).unwrap_err();
-        assert_eq!(unauthorized_res, ContractError::Unauthorized {});
+        assert!(msg.initial_mints.is_empty());
     }
 
     #[test]
-    fn test_revoke() {
+    fn test_migrate_legacy_tokens() {
         let mut deps = mock_dependencies();
-
         init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-
-        let revoke_msg = ExecuteMsg::<Extension, Empty>::Revoke {
-            spender: ADDR1.to_string(),
-            token_id: "1".to_string(),
+        // token records of cw721-base, with the metadata next to the owner and no stats
+        MIGRATION.remove(deps.as_mut().storage);
+        let legacy_tokens: Map<&str, LegacyTokenInfo<Extension>> = Map::new("tokens");
+        for (token_id, owner) in [("legacy", ADDR2), ("legacy-2", ADDR1), ("legacy-3", ADDR1)] {
+            legacy_tokens
+                .save(
+                    deps.as_mut().storage,
+                    token_id,
+                    &LegacyTokenInfo {
+                        owner: Addr::unchecked(owner),
+                        approvals: vec![],
+                        token_uri: Some(format!("ipfs://{}", token_id)),
+                        extension: Some(Empty {}),
+                    },
+                )
+                .unwrap();
+        }
+        let nft_info = |deps: Deps, token_id: &str| {
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::NftInfo {
+                    token_id: token_id.to_string(),
+                },
+                &TestExtension,
+            )
+            .and_then(|res| from_binary::<NftInfoResponse<Extension>>(&res))
+        };
+        let stats = |deps: Deps| -> StatsResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::Stats {}, &TestExtension).unwrap())
+                .unwrap()
+        };
+        let transfer = |deps: DepsMut, sender: &str, token_id: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::TransferNft {
+                    recipient: ADDR1.to_string(),
+                    token_id: token_id.to_string(),
+                },
+                &TestExtension,
+            )
+        };
+        let migrate_tokens = |deps: DepsMut, sender: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::MigrateTokens { limit: Some(2) },
+                &TestExtension,
+            )
         };
+        nft_info(deps.as_ref(), "legacy").unwrap_err();
 
-        let revoke_res = execute::<Extension, Empty, Empty, CustomError>(
+        // the first batch runs in migrate, the admin runs the next ones
+        let res = migrate::<Extension, Empty, CustomError>(
             deps.as_mut(),
             mock_env(),
-            mock_info(ADDR1, &[]),
-            revoke_msg,
+            MigrateMsg { limit: Some(2) },
         )
         .unwrap();
         assert_eq!(
-            revoke_res.attributes,
-            [
-                attr("action", "revoke"),
-                attr("sender", ADDR1),
-                attr("spender", ADDR1),
-                attr("token_id", "1"),
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("migrated_tokens", "2"),
+                attr("migration_done", "false"),
             ]
         );
-    }
-
-    #[test]
-    fn test_approve_all() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-
-        let approve_all_msg = ExecuteMsg::<Extension, Empty>::ApproveAll {
-            operator: ADDR2.to_string(),
-            expires: Some(Expiration::AtHeight(50000)),
-        };
+        nft_info(deps.as_ref(), "legacy-2").unwrap();
+        nft_info(deps.as_ref(), "legacy-3").unwrap_err();
 
-        let approve_all_res = execute::<Extension, Empty, Empty, CustomError>(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            approve_all_msg,
-        )
-        .unwrap();
+        // executes are paused and the stats are not reported until the last batch
         assert_eq!(
-            approve_all_res.attributes,
-            [
-                attr("action", "approve_all"),
-                attr("sender", ADDR1),
-                attr("operator", ADDR2),
-            ]
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Stats {},
+                &TestExtension
+            )
+            .unwrap_err(),
+            StdError::generic_err(
+                "stats_seeding: Stats are incomplete until the migration is done"
+            )
         );
-
-        let all_operators_query_msg = QueryMsg::<Empty>::AllOperators {
-            owner: ADDR1.to_string(),
-            include_expired: None,
-            start_after: None,
-            limit: None,
-        };
-
-        let owner_of_res: OperatorsResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), all_operators_query_msg).unwrap(),
-        )
-        .unwrap();
         assert_eq!(
-            owner_of_res,
-            OperatorsResponse {
-                operators: vec![Approval {
-                    spender: ADDR2.to_string(),
-                    expires: Expiration::AtHeight(50000),
-                }]
-            }
+            transfer(deps.as_mut(), ADDR2, "legacy").unwrap_err(),
+            ContractError::MigrationPending {}
         );
-
-        let expired_approve_all_msg = ExecuteMsg::<Extension, Empty>::ApproveAll {
-            operator: ADDR2.to_string(),
-            expires: Some(Expiration::AtHeight(10)),
-        };
-
-        let expired_approve_all_res = execute::<Extension, Empty, Empty, CustomError>(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            expired_approve_all_msg,
-        )
-        .unwrap_err();
-
-        assert_eq!(expired_approve_all_res, ContractError::Expired {});
-    }
-
-    #[test]
-    fn test_revoke_all() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-
-        let revoke_all_msg = ExecuteMsg::<Extension, Empty>::RevokeAll {
-            operator: ADDR2.to_string(),
-        };
-
-        let revoke_all_res = execute::<Extension, Empty, Empty, CustomError>(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(ADDR1, &[]),
-            revoke_all_msg,
-        )
-        .unwrap();
-
         assert_eq!(
-            revoke_all_res.attributes,
-            [
-                attr("action", "revoke_all"),
-                attr("sender", ADDR1),
-                attr("operator", ADDR2),
-            ]
+            migrate_tokens(deps.as_mut(), ADDR2).unwrap_err(),
+            ContractError::Unauthorized {}
         );
-    }
-
-    #[test]
-    fn test_transfer_nft() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        let transfer_nft_res = transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
-
+        let res = migrate_tokens(deps.as_mut(), ADDR1).unwrap();
         assert_eq!(
-            transfer_nft_res.attributes,
-            [
-                attr("action", "transfer_nft"),
+            res.attributes,
+            vec![
+                attr("action", "migrate_tokens"),
                 attr("sender", ADDR1),
-                attr("recipient", ADDR2),
-                attr("token_id", "1"),
+                attr("migrated_tokens", "1"),
+                attr("migration_done", "true"),
             ]
         );
-
-        let owner_of_query_msg = QueryMsg::<Empty>::OwnerOf {
-            token_id: "1".to_string(),
-            include_expired: None,
-        };
-
-        let owner_of_res: OwnerOfResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), owner_of_query_msg).unwrap(),
-        )
-        .unwrap();
         assert_eq!(
-            owner_of_res,
-            OwnerOfResponse {
-                owner: ADDR2.to_string(),
-                approvals: vec![],
-            }
+            nft_info(deps.as_ref(), "legacy-3").unwrap().token_uri,
+            Some("ipfs://legacy-3".to_string())
         );
-
-        // ADDR1 is unauthorized
-        let transfer_err = transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap_err();
-        assert_eq!(transfer_err, ContractError::Unauthorized {});
-
-        mint(deps.as_mut(), ADDR2, "2").unwrap();
-
-        let tokens_query_msg = QueryMsg::Tokens {
-            owner: ADDR2.to_string(),
-            start_after: None,
-            limit: None,
-        };
-
-        let tokens_query_res: TokensResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), tokens_query_msg).unwrap(),
-        )
-        .unwrap();
-
         assert_eq!(
-            tokens_query_res,
-            TokensResponse {
-                tokens: vec!["1".to_string(), "2".to_string()]
+            nft_info(deps.as_ref(), "legacy").unwrap(),
+            NftInfoResponse {
+                token_uri: Some("ipfs://legacy".to_string()),
+                extension: Some(Empty {}),
             }
         );
-    }
-
-    #[test]
-    fn test_approve_and_transfer() {
-        let mut deps = mock_dependencies();
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-        transfer_nft(deps.as_mut(), ADDR2, ADDR2).unwrap();
-
-        let all_tokens_query_msg = QueryMsg::AllTokens {
-            start_after: None,
-            limit: None,
-        };
-
-        let all_tokens_query_res: TokensResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), all_tokens_query_msg).unwrap(),
-        )
-        .unwrap();
         assert_eq!(
-            all_tokens_query_res,
-            TokensResponse {
-                tokens: vec!["1".to_string()]
-            }
-        )
-    }
-
-    #[test]
-    fn test_send_nft() {
-        let mut deps = mock_dependencies();
-        let contract_addr = mock_env().contract.address;
-
-        init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-
-        approve(deps.as_mut(), ADDR1, contract_addr.as_ref()).unwrap();
-
-        let send_nft_msg = ExecuteMsg::<Extension, Empty>::SendNft {
-            contract: contract_addr.to_string(),
-            token_id: "1".to_string(),
-            msg: to_binary(&ExecuteMsg::TransferNft::<Extension, Empty> {
-                recipient: ADDR2.to_string(),
-                token_id: "1".to_string(),
-            })
-            .unwrap(),
-        };
-
-        execute::<Extension, Empty, Empty, CustomError>(
+            get_tokens().load(&deps.storage, "legacy").unwrap().owner,
+            Addr::unchecked(ADDR2)
+        );
+        // the stats are seeded from the live tokens
+        let seeded = stats(deps.as_ref());
+        assert_eq!((seeded.minted, seeded.unique_holders), (3, 2));
+
+        // legacy tokens move like the others
+        transfer(deps.as_mut(), ADDR2, "legacy").unwrap();
+        let after = stats(deps.as_ref());
+        assert_eq!((after.transferred, after.unique_holders), (1, 1));
+        mint(deps.as_mut(), ADDR2, "1").unwrap();
+        assert_eq!(nft_info(deps.as_ref(), "1").unwrap().token_uri, None);
+
+        // a done migration and tracked stats are skipped when it runs again
+        let res = migrate::<Extension, Empty, CustomError>(
             deps.as_mut(),
             mock_env(),
-            mock_info(ADDR1, &[]),
-            send_nft_msg,
+            MigrateMsg::default(),
         )
         .unwrap();
+        assert_eq!(res.attributes[1], attr("migrated_tokens", "0"));
+        assert_eq!(res.attributes[2], attr("migration_done", "true"));
+        assert_eq!(stats(deps.as_ref()).minted, 4);
     }
 
     #[test]
-    fn test_burn() {
-        let mut deps = mock_dependencies();
+    fn test_extension_hooks() {
+        struct HookExtension;
+
+        impl Cw721Extension for HookExtension {
+            type Extension = Extension;
+            type ExecuteMsg = Empty;
+            type QueryMsg = Empty;
+            type CustomMsg = Empty;
+            type Error = CustomError;
+
+            fn after_mint(
+                &self,
+                _deps: DepsMut,
+                _env: &Env,
+                _info: &MessageInfo,
+                token_id: &str,
+                res: Response,
+            ) -> Result<Response, ContractError> {
+                Ok(res.add_attribute("hooked_mint", token_id))
+            }
 
+            fn before_transfer(
+                &self,
+                _deps: Deps,
+                _env: &Env,
+                _info: &MessageInfo,
+                _token_id: &str,
+                recipient: &str,
+            ) -> Result<(), ContractError> {
+                if recipient == ADDR2 {
+                    return Err(ContractError::CustomError(CustomError::CustomError {}));
+                }
+                Ok(())
+            }
+        }
+
+        let mut deps = mock_dependencies();
         init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-        let burn_msg = ExecuteMsg::Burn {
-            token_id: "1".to_string(),
-        };
 
-        execute::<Extension, Empty, Empty, CustomError>(
+        let mint_res = execute(
             deps.as_mut(),
             mock_env(),
             mock_info(ADDR1, &[]),
-            burn_msg.clone(),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: "1".to_string(),
+                owner: ADDR1.to_string(),
+                token_uri: None,
+                extension: None,
+            }),
+            &HookExtension,
         )
         .unwrap();
-        // Cannot burn same nft again
-        execute::<Extension, Empty, Empty, CustomError>(
+        assert_eq!(mint_res.attributes.last(), Some(&attr("hooked_mint", "1")));
+
+        // before_transfer vetoes the transfer, ownership is untouched
+        let transfer_err = execute(
             deps.as_mut(),
             mock_env(),
             mock_info(ADDR1, &[]),
-            burn_msg,
+            ExecuteMsg::TransferNft {
+                recipient: ADDR2.to_string(),
+                token_id: "1".to_string(),
+            },
+            &HookExtension,
         )
         .unwrap_err();
+        assert_eq!(
+            transfer_err,
+            ContractError::CustomError(CustomError::CustomError {})
+        );
+        assert_eq!(get_tokens().load(&deps.storage, "1").unwrap().owner, ADDR1);
     }
 
     #[test]
-    fn query_contract_info() {
+    fn test_unsupported_extension() {
         let mut deps = mock_dependencies();
         init(deps.as_mut());
-        mint(deps.as_mut(), ADDR1, "1").unwrap();
-        mint(deps.as_mut(), ADDR1, "2").unwrap();
 
-        let contract_info_query_msg = QueryMsg::<Empty>::ContractInfo {};
-        let contract_info_query_res: ContractInfoResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), contract_info_query_msg).unwrap(),
+        let execute_err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            ExecuteMsg::Extension { msg: Empty {} },
+            &TestExtension,
         )
-        .unwrap();
-        assert_eq!(
-            contract_info_query_res,
-            ContractInfoResponse {
-                name: "cw721-contract".to_string(),
-                symbol: "cw721".to_string(),
-            }
-        );
-
-        let nft_info_query_msg = QueryMsg::<Empty>::NftInfo {
-            token_id: "1".to_string(),
-        };
+        .unwrap_err();
+        assert_eq!(execute_err, ContractError::UnsupportedExtensionMsg {});
 
-        let nft_info_query_res: NftInfoResponse<Extension> = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), nft_info_query_msg).unwrap(),
+        let query_err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Extension { msg: Empty {} },
+            &TestExtension,
         )
-        .unwrap();
+        .unwrap_err();
         assert_eq!(
-            nft_info_query_res,
-            NftInfoResponse {
-                token_uri: None,
-                extension: None,
-            }
+            query_err,
+            StdError::generic_err(UNSUPPORTED_EXTENSION_QUERY)
         );
-
-        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
-
-        let all_nft_info_query_msg = QueryMsg::<Empty>::AllNftInfo {
-            token_id: "1".to_string(),
-            include_expired: Some(true),
-        };
-
-        let all_nft_info_query_res: AllNftInfoResponse<Extension> = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), all_nft_info_query_msg).unwrap(),
-        )
-        .unwrap();
-
-        assert_eq!(
-            all_nft_info_query_res,
-            AllNftInfoResponse {
-                access: OwnerOfResponse {
-                    owner: ADDR1.to_string(),
-                    approvals: vec![Approval {
-                        spender: ADDR2.to_string(),
-                        expires: Expiration::AtHeight(50000),
-                    }],
-                },
-                info: NftInfoResponse {
-                    token_uri: None,
-                    extension: None,
-                },
-            }
-        )
     }
 
     #[test]
-    fn test_query_minter() {
+    fn test_migrate_keeps_enumerable_mode() {
         let mut deps = mock_dependencies();
         init(deps.as_mut());
+        let migrate_msg = |deps: DepsMut| {
+            migrate::<Extension, Empty, CustomError>(deps, mock_env(), MigrateMsg::default())
+        };
 
-        let minter_query_msg = QueryMsg::<Empty>::Minter {};
-
-        let minter_query_res: MinterResponse = from_binary(
-            &query::<Extension, Empty>(deps.as_ref(), mock_env(), minter_query_msg).unwrap(),
-        )
-        .unwrap();
+        // a build of the other mode would leave the owner index stale
+        ENUMERABLE
+            .save(deps.as_mut().storage, &!ENUMERABLE_BUILD)
+            .unwrap();
         assert_eq!(
-            minter_query_res,
-            MinterResponse {
-                minter: ADDR1.to_string()
+            migrate_msg(deps.as_mut()).unwrap_err(),
+            ContractError::EnumerableChanged {
+                enumerable: !ENUMERABLE_BUILD
             }
         );
+
+        ENUMERABLE
+            .save(deps.as_mut().storage, &ENUMERABLE_BUILD)
+            .unwrap();
+        migrate_msg(deps.as_mut()).unwrap();
+
+        // collections of cw721-base and earlier versions wrote the owner index
+        ENUMERABLE.remove(deps.as_mut().storage);
+        let res = migrate_msg(deps.as_mut());
+        if ENUMERABLE_BUILD {
+            res.unwrap();
+            assert!(ENUMERABLE.load(&deps.storage).unwrap());
+        } else {
+            assert_eq!(
+                res.unwrap_err(),
+                ContractError::EnumerableChanged { enumerable: true }
+            );
+        }
     }
 }
@@ -1,40 +1,272 @@
-use cosmwasm_std::StdError;
+use crate::state::TokenInfo;
+use cosmwasm_std::{Coin, StdError, Uint128};
+use cw721_simple_derive::ErrorCode;
+use std::any::type_name;
 use std::error::Error;
 use std::fmt::Debug;
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, ErrorCode, Debug, PartialEq)]
 pub enum ContractError<E = CustomError>
 where
     E: Error + Debug + PartialEq,
 {
     #[error("{0}")]
+    #[code = "std"]
     Std(#[from] StdError),
 
     #[error("Unauthorized")]
+    #[code = "unauthorized"]
     Unauthorized {},
 
     #[error("token_id already claimed")]
+    #[code = "claimed"]
     Claimed {},
 
     #[error("Cannot set approval that is already expired")]
+    #[code = "expired"]
     Expired {},
 
+    #[error("Token not found: {token_id}")]
+    #[code = "token_not_found"]
+    TokenNotFound { token_id: String },
+
     #[error("Approval not found for: {spender}")]
+    #[code = "approval_not_found"]
     ApprovalNotFound { spender: String },
 
     #[error("Saving contract info failed")]
+    #[code = "contract_info_save_error"]
     ContractInfoSaveError {},
 
     #[error("Saving minter failed")]
+    #[code = "minter_save_error"]
     MinterSaveError {},
 
-    #[error("CustomError")]
+    #[error("Unsupported extension message")]
+    #[code = "unsupported_extension_msg"]
+    UnsupportedExtensionMsg {},
+
+    #[error("Token enumeration is disabled")]
+    #[code = "enumeration_disabled"]
+    EnumerationDisabled {},
+
+    #[error("Invalid URL for {field}: {url}")]
+    #[code = "invalid_url"]
+    InvalidUrl { field: String, url: String },
+
+    #[error("token_id is empty")]
+    #[code = "token_id_empty"]
+    TokenIdEmpty {},
+
+    #[error("token_id is longer than {max_length} bytes")]
+    #[code = "token_id_too_long"]
+    TokenIdTooLong { max_length: u32 },
+
+    #[error("token_id {token_id} contains an invalid character: {invalid:?}")]
+    #[code = "token_id_invalid_char"]
+    TokenIdInvalidChar { token_id: String, invalid: char },
+
+    #[error("token_id {token_id} is not a number without leading zeros")]
+    #[code = "token_id_not_numeric"]
+    TokenIdNotNumeric { token_id: String },
+
+    #[error("token_id {token_id} was burned and can't be minted again")]
+    #[code = "token_burned"]
+    TokenBurned { token_id: String },
+
+    #[error("No pending transfer for: {token_id}")]
+    #[code = "pending_transfer_not_found"]
+    PendingTransferNotFound { token_id: String },
+
+    #[error("Pending transfer of {token_id} expired")]
+    #[code = "pending_transfer_expired"]
+    PendingTransferExpired { token_id: String },
+
+    #[error("Token {token_id} is already owned by the recipient")]
+    #[code = "recipient_is_owner"]
+    RecipientIsOwner { token_id: String },
+
+    #[error("Address {address} can't receive tokens")]
+    #[code = "address_blocked"]
+    AddressBlocked { address: String },
+
+    #[error("Token {token_id} is frozen: {reason}")]
+    #[code = "token_frozen"]
+    TokenFrozen { token_id: String, reason: String },
+
+    #[error("Token {token_id} is locked")]
+    #[code = "token_locked"]
+    TokenLocked { token_id: String },
+
+    #[error("Transfer fee amount is zero")]
+    #[code = "zero_transfer_fee"]
+    ZeroTransferFee {},
+
+    #[error("Transfer fee is paid in {expected}, not {denom}")]
+    #[code = "wrong_fee_denom"]
+    WrongFeeDenom { expected: String, denom: String },
+
+    #[error("Transfer fee is {required}, paid {paid}")]
+    #[code = "wrong_fee_amount"]
+    WrongFeeAmount { required: Coin, paid: Coin },
+
+    #[error("Price is zero")]
+    #[code = "zero_price"]
+    ZeroPrice {},
+
+    #[error("cw20 {address} is not accepted as payment")]
+    #[code = "unsupported_cw20"]
+    UnsupportedCw20 { address: String },
+
+    #[error("Mint costs {price}, received {amount}")]
+    #[code = "wrong_cw20_amount"]
+    WrongCw20Amount { price: Uint128, amount: Uint128 },
+
+    #[error("Paid mint is disabled")]
+    #[code = "paid_mint_disabled"]
+    PaidMintDisabled {},
+
+    #[error("Mint is paid in {expected}, not {denom}")]
+    #[code = "wrong_payment_denom"]
+    WrongPaymentDenom { expected: String, denom: String },
+
+    #[error("Mint costs {price}, paid {paid}")]
+    #[code = "wrong_payment_amount"]
+    WrongPaymentAmount { price: Coin, paid: Coin },
+
+    #[error("Payee shares add up to {total} basis points instead of 10000")]
+    #[code = "invalid_shares"]
+    InvalidShares { total: u32 },
+
+    #[error("Payee {address} is listed twice")]
+    #[code = "duplicate_payee"]
+    DuplicatePayee { address: String },
+
+    #[error("More than {max} payees")]
+    #[code = "too_many_payees"]
+    TooManyPayees { max: u32 },
+
+    #[error("Nothing to withdraw")]
+    #[code = "nothing_to_withdraw"]
+    NothingToWithdraw {},
+
+    #[error("Grant scope is empty")]
+    #[code = "empty_scope"]
+    EmptyScope {},
+
+    #[error("Grant scope lists more than {max} tokens")]
+    #[code = "scope_too_large"]
+    ScopeTooLarge { max: u32 },
+
+    #[error("Tokens are being migrated, executes are paused until migrate_tokens is done")]
+    #[code = "migration_pending"]
+    MigrationPending {},
+
+    #[error(
+        "Migration can't change the enumerable mode of the collection, enumerable: {enumerable}"
+    )]
+    #[code = "enumerable_changed"]
+    EnumerableChanged { enumerable: bool },
+
+    #[error("Stats are incomplete until the migration is done")]
+    #[code = "stats_seeding"]
+    StatsSeeding {},
+
+    #[error("{0}")]
+    #[code = "custom"]
     CustomError(E),
 }
 
+/// Queries answer with StdError. Missing tokens and approvals stay NotFound as in cw721-base,
+/// the other contract errors are kept as generic errors carrying their code
+impl<E> From<ContractError<E>> for StdError
+where
+    E: Error + Debug + PartialEq,
+{
+    fn from(err: ContractError<E>) -> Self {
+        match err {
+            ContractError::Std(err) => err,
+            ContractError::TokenNotFound { .. } => StdError::not_found(type_name::<TokenInfo>()),
+            ContractError::ApprovalNotFound { .. } => StdError::not_found("Approval not found"),
+            err => StdError::generic_err(format!("{}: {}", err.code(), err)),
+        }
+    }
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum CustomError {
     #[error("CustomError")]
     CustomError {},
 }
+
+#[cfg(test)]
+mod error_tests {
+    use crate::error::{ContractError, CustomError};
+    use cosmwasm_std::StdError;
+    use thiserror::Error;
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    enum OtherError {
+        #[error("Wrapped: {0}")]
+        Wrapped(CustomError),
+    }
+
+    #[test]
+    fn test_map_custom() {
+        let err = ContractError::CustomError(CustomError::CustomError {});
+        let mapped: ContractError<OtherError> = err.map_custom(OtherError::Wrapped);
+        assert_eq!(
+            mapped,
+            ContractError::CustomError(OtherError::Wrapped(CustomError::CustomError {}))
+        );
+        assert_eq!(mapped.code(), "custom");
+        // the inner message is kept
+        assert_eq!(mapped.to_string(), "Wrapped: CustomError");
+
+        let err: ContractError = ContractError::Unauthorized {};
+        let mapped: ContractError<OtherError> = err.map_custom(OtherError::Wrapped);
+        assert_eq!(mapped, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_code_keeps_message() {
+        // the code sits next to the message, which stays the one of cw721-base
+        let err: ContractError = ContractError::Unauthorized {};
+        assert_eq!(err.code(), "unauthorized");
+        assert_eq!(err.to_string(), "Unauthorized");
+        let err: ContractError = ContractError::TokenFrozen {
+            token_id: "1".to_string(),
+            reason: "dispute".to_string(),
+        };
+        assert_eq!(err.code(), "token_frozen");
+        assert_eq!(err.to_string(), "Token 1 is frozen: dispute");
+    }
+
+    #[test]
+    fn test_into_std_error() {
+        // missing tokens and approvals keep the error kind of cw721-base
+        let err: ContractError = ContractError::TokenNotFound {
+            token_id: "1".to_string(),
+        };
+        assert!(matches!(StdError::from(err), StdError::NotFound { .. }));
+        let err: ContractError = ContractError::ApprovalNotFound {
+            spender: "spender".to_string(),
+        };
+        assert_eq!(
+            StdError::from(err),
+            StdError::not_found("Approval not found")
+        );
+
+        let err: ContractError = ContractError::TokenLocked {
+            token_id: "1".to_string(),
+        };
+        assert_eq!(
+            StdError::from(err),
+            StdError::generic_err("token_locked: Token 1 is locked")
+        );
+
+        let err: ContractError = ContractError::Std(StdError::generic_err("std"));
+        assert_eq!(StdError::from(err), StdError::generic_err("std"));
+    }
+}
@@ -1,10 +1,23 @@
 use crate::error::ContractError;
+use crate::msg::{CollectionInfoMsg, MintMsg, PayeeMsg, TransferFeeMsg};
 use crate::state::{
-    decrement_tokens, get_minter, get_tokens, increment_tokens, Approval, TokenInfo, OPERATORS,
+    allow_remint, append_history, decrement_tokens, get_address_list_mode, get_admin, get_locks,
+    get_minter, get_pending_transfers, get_token_id_policy, get_token_metadata, get_tokens,
+    increment_tokens, load_pending_transfer, load_token, migrate_token_batch, migration_pending,
+    record_burn, record_mint, record_transfer, AddressListMode, Approval, CollectionInfo,
+    GrantScope, HistoryAction, HistoryEntry, Lock, Payee, PendingTransfer, ScopedGrant,
+    TokenIdPolicy, TokenInfo, TokenMetadata, TransferFee, ADDRESS_LIST, ADDRESS_LIST_MODE,
+    BURNED_TOKENS, COLLECTION_INFO, CW20_CLAIMABLE, CW20_PRICES, CW20_PROCEEDS,
+    DEFAULT_MIGRATION_LIMIT, FROZEN_TOKENS, LOCK_MANAGERS, MAX_PAYEES, MAX_SCOPED_TOKENS,
+    NATIVE_CLAIMABLE, NATIVE_PRICE, OPERATORS, PAYEES, SCOPED_GRANTS, TOTAL_SHARES_BPS,
+    TRANSFER_FEE,
 };
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    to_binary, Addr, Api, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
 use cw721::{CustomMsg, Cw721ReceiveMsg, Expiration};
-use cw721_base::MintMsg;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::error::Error;
@@ -12,7 +25,7 @@ use std::fmt::Debug;
 
 pub fn mint<T, C, E>(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: MintMsg<T>,
 ) -> Result<Response<C>, ContractError<E>>
@@ -27,29 +40,219 @@ where
         return Err(ContractError::Unauthorized {});
     }
 
+    let owner = msg.owner.clone();
+    let token_id = _mint(deps, &env, msg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("minter", info.sender)
+        .add_attribute("owner", owner)
+        .add_attribute("token_id", token_id))
+}
+
+/// mints the token paid by the cw20 transfer, info.sender is the cw20 contract.
+/// The price must be paid exactly, the amount is added to the proceeds of the cw20
+pub fn cw20_mint<T, C, E>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    msg: MintMsg<T>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let price = CW20_PRICES
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::UnsupportedCw20 {
+            address: info.sender.to_string(),
+        })?;
+    if amount != price {
+        return Err(ContractError::WrongCw20Amount { price, amount });
+    }
+
+    let owner = msg.owner.clone();
+    let token_id = _mint(deps.branch(), &env, msg)?;
+    CW20_PROCEEDS.update(deps.storage, &info.sender, |proceeds| -> StdResult<_> {
+        Ok(proceeds.unwrap_or_default().checked_add(amount)?)
+    })?;
+    for (payee, share) in split_revenue(deps.storage, amount)? {
+        CW20_CLAIMABLE.update(deps.storage, (&payee, &info.sender), |claimable| {
+            add_claimable(claimable, share)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cw20_mint")
+        .add_attribute("cw20", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("owner", owner)
+        .add_attribute("token_id", token_id))
+}
+
+/// mints the token paid with the native price in the funds, the sender is the buyer
+pub fn paid_mint<T, C, E>(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: MintMsg<T>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let price = NATIVE_PRICE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::PaidMintDisabled {})?;
+    if let Some(coin) = info.funds.iter().find(|c| c.denom != price.denom) {
+        return Err(ContractError::WrongPaymentDenom {
+            expected: price.denom,
+            denom: coin.denom.clone(),
+        });
+    }
+    let paid = Coin {
+        denom: price.denom.clone(),
+        amount: info.funds.iter().map(|c| c.amount).sum::<Uint128>(),
+    };
+    if paid != price {
+        return Err(ContractError::WrongPaymentAmount { price, paid });
+    }
+
+    let owner = msg.owner.clone();
+    let token_id = _mint(deps.branch(), &env, msg)?;
+    for (payee, share) in split_revenue(deps.storage, paid.amount)? {
+        NATIVE_CLAIMABLE.update(deps.storage, (&payee, &paid.denom), |claimable| {
+            add_claimable(claimable, share)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "paid_mint")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("price", paid.to_string())
+        .add_attribute("owner", owner)
+        .add_attribute("token_id", token_id))
+}
+
+/// splits the revenue between the payees by their shares, the rounding dust goes to the
+/// first payee. The admin receives all of it while no payees are set
+fn split_revenue(storage: &dyn Storage, amount: Uint128) -> StdResult<Vec<(Addr, Uint128)>> {
+    let payees = PAYEES.may_load(storage)?.unwrap_or_default();
+    if payees.is_empty() {
+        return Ok(vec![(get_admin(storage)?, amount)]);
+    }
+
+    let mut shares: Vec<(Addr, Uint128)> = payees
+        .into_iter()
+        .map(|payee| {
+            let share = amount.multiply_ratio(payee.shares_bps, TOTAL_SHARES_BPS);
+            (payee.address, share)
+        })
+        .collect();
+    let split: Uint128 = shares.iter().map(|(_, share)| share).sum();
+    shares[0].1 += amount.checked_sub(split)?;
+    Ok(shares)
+}
+
+fn add_claimable(claimable: Option<Uint128>, share: Uint128) -> StdResult<Uint128> {
+    Ok(claimable.unwrap_or_default().checked_add(share)?)
+}
+
+/// validates and stores the token without checking the minter, returns the minted token_id.
+/// Shared by mint and the genesis mints of instantiate, the token_id must be folded
+pub fn _mint<T, E>(deps: DepsMut, env: &Env, msg: MintMsg<T>) -> Result<String, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+{
+    // the token_id is already folded, see fold_token_id
+    let token_id = msg.token_id;
+    validate_token_id(&get_token_id_policy(deps.storage)?, &token_id)?;
+
+    // burned ids stay burned, unless the collection allows re-minting them
+    if BURNED_TOKENS.has(deps.storage, &token_id) {
+        if !allow_remint(deps.storage)? {
+            return Err(ContractError::TokenBurned { token_id });
+        }
+        BURNED_TOKENS.remove(deps.storage, &token_id);
+    }
+
     // create the token
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    check_recipient(deps.storage, &owner)?;
     let token = TokenInfo {
-        owner: deps.api.addr_validate(&msg.owner)?,
+        owner: owner.clone(),
         approvals: vec![],
+    };
+    let metadata = TokenMetadata {
         token_uri: msg.token_uri,
         extension: msg.extension,
     };
 
-    get_tokens().update(deps.storage, &msg.token_id, |old| match old {
+    get_tokens().update(deps.storage, &token_id, |old| match old {
         Some(_) => Err(ContractError::Claimed {}),
         None => Ok(token),
     })?;
+    get_token_metadata().save(deps.storage, &token_id, &metadata)?;
 
     increment_tokens(deps.storage)?;
+    record_mint(deps.storage, &owner, env.block.time)?;
+    append_history(
+        deps.storage,
+        &token_id,
+        &HistoryEntry::new(&env.block, None, Some(owner), HistoryAction::Mint),
+    )?;
 
-    Ok(Response::new()
-        .add_attribute("action", "mint")
-        .add_attribute("minter", info.sender)
-        .add_attribute("owner", msg.owner)
-        .add_attribute("token_id", msg.token_id))
+    Ok(token_id)
+}
+
+/// the token_id as it is stored, folded by the policy of the collection.
+/// Applied once to every message and query naming a token before it is handled
+pub fn fold_token_id(storage: &dyn Storage, token_id: &str) -> StdResult<String> {
+    Ok(get_token_id_policy(storage)?.fold(token_id))
+}
+
+pub fn validate_token_id<E>(policy: &TokenIdPolicy, token_id: &str) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    if token_id.is_empty() {
+        return Err(ContractError::TokenIdEmpty {});
+    }
+    if token_id.len() > policy.max_length as usize {
+        return Err(ContractError::TokenIdTooLong {
+            max_length: policy.max_length,
+        });
+    }
+
+    if policy.numeric_only {
+        if !token_id.chars().all(|c| c.is_ascii_digit())
+            || (token_id.starts_with('0') && token_id != "0")
+        {
+            return Err(ContractError::TokenIdNotNumeric {
+                token_id: token_id.to_string(),
+            });
+        }
+        return Ok(());
+    }
+
+    let invalid = token_id.chars().find(|c| match &policy.allowed_chars {
+        Some(classes) => !classes.iter().any(|class| class.contains(*c)),
+        None => c.is_whitespace() || c.is_control(),
+    });
+    match invalid {
+        Some(invalid) => Err(ContractError::TokenIdInvalidChar {
+            token_id: token_id.to_string(),
+            invalid,
+        }),
+        None => Ok(()),
+    }
 }
 
-pub fn approve<T, C, E>(
+pub fn approve<C, E>(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -58,11 +261,10 @@ pub fn approve<T, C, E>(
     expires: Option<Expiration>,
 ) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
     C: CustomMsg,
 {
-    _update_approvals::<T, E>(deps, &env, &info, &spender, &token_id, true, expires)?;
+    _update_approvals::<E>(deps, &env, &info, &spender, &token_id, true, expires)?;
 
     Ok(Response::new()
         .add_attribute("action", "approve")
@@ -71,7 +273,7 @@ where
         .add_attribute("token_id", token_id))
 }
 
-pub fn revoke<T, C, E>(
+pub fn revoke<C, E>(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -79,11 +281,10 @@ pub fn revoke<T, C, E>(
     token_id: String,
 ) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
     C: CustomMsg,
 {
-    _update_approvals::<T, E>(deps, &env, &info, &spender, &token_id, false, None)?;
+    _update_approvals::<E>(deps, &env, &info, &spender, &token_id, false, None)?;
 
     Ok(Response::new()
         .add_attribute("action", "revoke")
@@ -138,212 +339,2845 @@ where
         .add_attribute("operator", operator))
 }
 
-pub fn burn<T, C, E>(
+/// grants the operator access to the tokens of the sender in the scope,
+/// replacing the previous scoped grant of the operator
+pub fn approve_scoped<C, E>(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    token_id: String,
+    operator: String,
+    scope: GrantScope,
+    expires: Option<Expiration>,
 ) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
     C: CustomMsg,
 {
-    let token = get_tokens().load(deps.storage, &token_id)?;
-    check_can_send::<T, E>(deps.as_ref(), &env, &info, &token)?;
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    match &scope {
+        GrantScope::Tokens { token_ids } if token_ids.is_empty() => {
+            return Err(ContractError::EmptyScope {})
+        }
+        GrantScope::Tokens { token_ids } if token_ids.len() > MAX_SCOPED_TOKENS as usize => {
+            return Err(ContractError::ScopeTooLarge {
+                max: MAX_SCOPED_TOKENS,
+            })
+        }
+        // an empty prefix would match every token, that is ApproveAll
+        GrantScope::Prefix { prefix } if prefix.is_empty() => {
+            return Err(ContractError::EmptyScope {})
+        }
+        _ => {}
+    }
 
-    get_tokens::<T>().remove(deps.storage, &token_id)?;
-    decrement_tokens(deps.storage)?;
+    // the scope names the token ids as they are stored
+    let policy = get_token_id_policy(deps.storage)?;
+    let scope = match scope {
+        GrantScope::Tokens { token_ids } => GrantScope::Tokens {
+            token_ids: token_ids.iter().map(|id| policy.fold(id)).collect(),
+        },
+        GrantScope::Prefix { prefix } => GrantScope::Prefix {
+            prefix: policy.fold(&prefix),
+        },
+    };
+
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    SCOPED_GRANTS.save(
+        deps.storage,
+        (&info.sender, &operator_addr),
+        &ScopedGrant { scope, expires },
+    )?;
 
     Ok(Response::new()
-        .add_attribute("action", "burn")
+        .add_attribute("action", "approve_scoped")
         .add_attribute("sender", info.sender)
-        .add_attribute("token_id", token_id))
+        .add_attribute("operator", operator))
 }
 
-pub fn transfer_nft<T, C, E>(
+pub fn revoke_scoped<C, E>(
     deps: DepsMut,
-    env: Env,
+    _env: Env,
     info: MessageInfo,
-    recipient: String,
-    token_id: String,
+    operator: String,
 ) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
     C: CustomMsg,
 {
-    _transfer_nft::<T, E>(deps, &env, &info, &recipient, &token_id)?;
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    SCOPED_GRANTS.remove(deps.storage, (&info.sender, &operator_addr));
 
     Ok(Response::new()
-        .add_attribute("action", "transfer_nft")
+        .add_attribute("action", "revoke_scoped")
         .add_attribute("sender", info.sender)
-        .add_attribute("recipient", recipient)
-        .add_attribute("token_id", token_id))
+        .add_attribute("operator", operator))
 }
 
-pub fn send_nft<T, C, E>(
+/// adds and removes addresses of the address list, and switches its mode when set.
+/// Can only be called by the admin
+pub fn update_address_list<C, E>(
     deps: DepsMut,
-    env: Env,
+    _env: Env,
     info: MessageInfo,
-    contract: String,
-    token_id: String,
-    msg: Binary,
+    mode: Option<AddressListMode>,
+    add: Vec<String>,
+    remove: Vec<String>,
 ) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
     C: CustomMsg,
 {
-    // Transfer token
-    _transfer_nft::<T, E>(deps, &env, &info, &contract, &token_id)?;
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    let send = Cw721ReceiveMsg {
-        sender: info.sender.to_string(),
-        token_id: token_id.clone(),
-        msg,
-    };
+    if let Some(mode) = mode {
+        ADDRESS_LIST_MODE.save(deps.storage, &mode)?;
+    }
+    for address in add {
+        ADDRESS_LIST.save(deps.storage, &deps.api.addr_validate(&address)?, &Empty {})?;
+    }
+    for address in remove {
+        ADDRESS_LIST.remove(deps.storage, &deps.api.addr_validate(&address)?);
+    }
 
-    // Send message
     Ok(Response::new()
-        .add_message(send.into_cosmos_msg(contract.clone())?)
-        .add_attribute("action", "send_nft")
-        .add_attribute("sender", info.sender)
-        .add_attribute("recipient", contract)
-        .add_attribute("token_id", token_id))
+        .add_attribute("action", "update_address_list")
+        .add_attribute("sender", info.sender))
 }
 
-fn _transfer_nft<T, E>(
+/// freezes the token against transfer, send, approve and burn. Can only be called by the admin
+pub fn freeze_token<C, E>(
     deps: DepsMut,
-    env: &Env,
-    info: &MessageInfo,
-    recipient: &str,
-    token_id: &str,
-) -> Result<TokenInfo<T>, ContractError<E>>
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+    reason: String,
+) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
+    C: CustomMsg,
 {
-    let mut token = get_tokens().load(deps.storage, token_id)?;
-    // ensure we have permissions
-    check_can_send(deps.as_ref(), env, info, &token)?;
-    // set owner and remove existing approvals
-    token.owner = deps.api.addr_validate(recipient)?;
-    token.approvals = vec![];
-    get_tokens().save(deps.storage, token_id, &token)?;
-    Ok(token)
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    load_token(deps.storage, &token_id)?;
+    FROZEN_TOKENS.save(deps.storage, &token_id, &reason)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze_token")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id)
+        .add_attribute("reason", reason))
 }
 
-#[allow(clippy::too_many_arguments)]
-fn _update_approvals<T, E>(
+pub fn unfreeze_token<C, E>(
     deps: DepsMut,
-    env: &Env,
-    info: &MessageInfo,
-    spender: &str,
-    token_id: &str,
-    // if add == false, remove. if add == true, remove then set with this expiration
-    add: bool,
-    expires: Option<Expiration>,
-) -> Result<TokenInfo<T>, ContractError<E>>
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
+    C: CustomMsg,
 {
-    let mut token = get_tokens().load(deps.storage, token_id)?;
-    // ensure we have permissions
-    check_can_approve(deps.as_ref(), env, info, &token)?;
-
-    // update the approval list (remove any for the same spender before adding)
-    let spender_addr = deps.api.addr_validate(spender)?;
-    token.approvals = token
-        .approvals
-        .into_iter()
-        .filter(|apr| apr.spender != spender_addr)
-        .collect();
-
-    // only difference between approve and revoke
-    if add {
-        // reject expired data as invalid
-        let expires = expires.unwrap_or_default();
-        if expires.is_expired(&env.block) {
-            return Err(ContractError::Expired {});
-        }
-        let approval = Approval {
-            spender: spender_addr,
-            expires,
-        };
-        token.approvals.push(approval);
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
     }
+    load_token(deps.storage, &token_id)?;
+    FROZEN_TOKENS.remove(deps.storage, &token_id);
 
-    get_tokens().save(deps.storage, token_id, &token)?;
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze_token")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
 
-    Ok(token)
+/// rejects blocked addresses, or addresses missing from the allowlist
+pub fn check_recipient<E>(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let listed = ADDRESS_LIST.has(storage, address);
+    let allowed = match get_address_list_mode(storage)? {
+        AddressListMode::Blocklist => !listed,
+        AddressListMode::Allowlist => listed,
+    };
+    if !allowed {
+        return Err(ContractError::AddressBlocked {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
 }
 
-/// returns true iff the sender can execute approve or reject on the contract
-pub fn check_can_approve<T, E>(
-    deps: Deps,
-    env: &Env,
-    info: &MessageInfo,
-    token: &TokenInfo<T>,
-) -> Result<(), ContractError<E>>
+pub fn check_not_frozen<E>(storage: &dyn Storage, token_id: &str) -> Result<(), ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
 {
-    // owner can approve
-    if token.owner == info.sender {
-        return Ok(());
+    match FROZEN_TOKENS.may_load(storage, token_id)? {
+        Some(reason) => Err(ContractError::TokenFrozen {
+            token_id: token_id.to_string(),
+            reason,
+        }),
+        None => Ok(()),
     }
-    // operator can approve
-    let op = OPERATORS.may_load(deps.storage, (&token.owner, &info.sender))?;
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(ContractError::Unauthorized {})
-            } else {
-                Ok(())
-            }
-        }
-        None => Err(ContractError::Unauthorized {}),
+}
+
+pub fn update_collection_info<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_info: CollectionInfoMsg,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
     }
+
+    let collection_info = validate_collection_info(deps.api, collection_info)?;
+    COLLECTION_INFO.save(deps.storage, &collection_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_collection_info")
+        .add_attribute("sender", info.sender))
 }
 
-/// returns true iff the sender can transfer ownership of the token
-pub fn check_can_send<T, E>(
-    deps: Deps,
-    env: &Env,
-    info: &MessageInfo,
-    token: &TokenInfo<T>,
-) -> Result<(), ContractError<E>>
+/// sets the price of paid mints, or disables them when unset
+pub fn update_native_price<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    price: Option<Coin>,
+) -> Result<Response<C>, ContractError<E>>
 where
-    T: Serialize + DeserializeOwned + Clone,
     E: Debug + PartialEq + Error,
+    C: CustomMsg,
 {
-    // owner can send
-    if token.owner == info.sender {
-        return Ok(());
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
     }
 
-    // any non-expired token approval can send
-    if token
-        .approvals
-        .iter()
-        .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
-    {
-        return Ok(());
+    match price {
+        Some(price) if price.amount.is_zero() => return Err(ContractError::ZeroPrice {}),
+        Some(price) => NATIVE_PRICE.save(deps.storage, &price)?,
+        None => NATIVE_PRICE.remove(deps.storage),
     }
 
-    // operator can send
-    let op = OPERATORS.may_load(deps.storage, (&token.owner, &info.sender))?;
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(ContractError::Unauthorized {})
-            } else {
-                Ok(())
+    Ok(Response::new()
+        .add_attribute("action", "update_native_price")
+        .add_attribute("sender", info.sender))
+}
+
+/// replaces the payees, the revenue already credited stays with the previous payees
+pub fn update_payees<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    payees: Vec<PayeeMsg>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if payees.len() > MAX_PAYEES as usize {
+        return Err(ContractError::TooManyPayees { max: MAX_PAYEES });
+    }
+
+    let mut validated: Vec<Payee> = Vec::with_capacity(payees.len());
+    for payee in payees {
+        let address = deps.api.addr_validate(&payee.address)?;
+        if validated.iter().any(|p| p.address == address) {
+            return Err(ContractError::DuplicatePayee {
+                address: payee.address,
+            });
+        }
+        validated.push(Payee {
+            address,
+            shares_bps: payee.shares_bps,
+        });
+    }
+    let total: u32 = validated.iter().map(|p| p.shares_bps as u32).sum();
+    let zero_share = validated.iter().any(|p| p.shares_bps == 0);
+    if !validated.is_empty() && (total != TOTAL_SHARES_BPS as u32 || zero_share) {
+        return Err(ContractError::InvalidShares { total });
+    }
+    PAYEES.save(deps.storage, &validated)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_payees")
+        .add_attribute("sender", info.sender))
+}
+
+/// sends all the native and cw20 revenue credited to the sender
+pub fn withdraw<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let native: Vec<Coin> = NATIVE_CLAIMABLE
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<_>>()?;
+    let cw20: Vec<(Addr, Uint128)> = CW20_CLAIMABLE
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    if native.is_empty() && cw20.is_empty() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    let mut res = Response::new();
+    for coin in &native {
+        NATIVE_CLAIMABLE.remove(deps.storage, (&info.sender, &coin.denom));
+    }
+    if !native.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: native,
+        });
+    }
+    for (token, amount) in cw20 {
+        CW20_CLAIMABLE.remove(deps.storage, (&info.sender, &token));
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: token.into_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(res
+        .add_attribute("action", "withdraw")
+        .add_attribute("payee", info.sender))
+}
+
+/// sets the mint price in the cw20 token, or stops accepting it when unset
+pub fn update_cw20_price<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token: String,
+    price: Option<Uint128>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token_addr = deps.api.addr_validate(&token)?;
+    match price {
+        // a cw20 transfer of zero fails, so the mint could never be paid
+        Some(price) if price.is_zero() => return Err(ContractError::ZeroPrice {}),
+        Some(price) => CW20_PRICES.save(deps.storage, &token_addr, &price)?,
+        None => CW20_PRICES.remove(deps.storage, &token_addr),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_cw20_price")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token", token))
+}
+
+pub fn update_transfer_fee<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    transfer_fee: Option<TransferFeeMsg>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match transfer_fee {
+        Some(transfer_fee) => {
+            let transfer_fee = validate_transfer_fee(deps.api, transfer_fee)?;
+            TRANSFER_FEE.save(deps.storage, &transfer_fee)?;
+        }
+        None => TRANSFER_FEE.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_transfer_fee")
+        .add_attribute("sender", info.sender))
+}
+
+/// migrates the next batch of tokens, can only be called by the admin
+pub fn migrate_tokens<T, C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    if info.sender != get_admin(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_MIGRATION_LIMIT);
+    let migrated = migrate_token_batch::<T>(deps.storage, limit)?;
+    let done = !migration_pending(deps.storage)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate_tokens")
+        .add_attribute("sender", info.sender)
+        .add_attribute("migrated_tokens", migrated.to_string())
+        .add_attribute("migration_done", done.to_string()))
+}
+
+/// validates the treasury address, a zero fee is rejected as it can't be sent
+pub fn validate_transfer_fee<E>(
+    api: &dyn Api,
+    msg: TransferFeeMsg,
+) -> Result<TransferFee, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    if msg.amount.amount.is_zero() {
+        return Err(ContractError::ZeroTransferFee {});
+    }
+    Ok(TransferFee {
+        amount: msg.amount,
+        treasury: api.addr_validate(&msg.treasury)?,
+    })
+}
+
+/// checks the funds match the transfer fee exactly and returns the message forwarding them
+/// to the treasury, so no funds stay in the contract. Funds are not checked while transfers are free
+pub fn collect_transfer_fee<E>(
+    storage: &dyn Storage,
+    funds: &[Coin],
+) -> Result<Option<BankMsg>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let fee = match TRANSFER_FEE.may_load(storage)? {
+        Some(fee) => fee,
+        None => return Ok(None),
+    };
+
+    if let Some(coin) = funds.iter().find(|c| c.denom != fee.amount.denom) {
+        return Err(ContractError::WrongFeeDenom {
+            expected: fee.amount.denom,
+            denom: coin.denom.clone(),
+        });
+    }
+    let paid = Coin {
+        denom: fee.amount.denom.clone(),
+        amount: funds.iter().map(|c| c.amount).sum::<Uint128>(),
+    };
+    if paid.amount != fee.amount.amount {
+        return Err(ContractError::WrongFeeAmount {
+            required: fee.amount,
+            paid,
+        });
+    }
+
+    Ok(Some(BankMsg::Send {
+        to_address: fee.treasury.into_string(),
+        amount: vec![paid],
+    }))
+}
+
+const MAX_URL_LENGTH: usize = 512;
+const URL_SCHEMES: [&str; 4] = ["https://", "http://", "ipfs://", "ar://"];
+
+/// validates the creator address and every URL of the collection info
+pub fn validate_collection_info<E>(
+    api: &dyn Api,
+    msg: CollectionInfoMsg,
+) -> Result<CollectionInfo, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    validate_url("image", msg.image.as_deref())?;
+    validate_url("banner_url", msg.banner_url.as_deref())?;
+    validate_url("external_link", msg.external_link.as_deref())?;
+    for link in &msg.social_links {
+        validate_url(&link.name, Some(&link.url))?;
+    }
+
+    Ok(CollectionInfo {
+        description: msg.description,
+        image: msg.image,
+        banner_url: msg.banner_url,
+        external_link: msg.external_link,
+        creator: msg
+            .creator
+            .map(|creator| api.addr_validate(&creator))
+            .transpose()?,
+        social_links: msg.social_links,
+    })
+}
+
+/// an URL has a known scheme, a host and no whitespace
+fn validate_url<E>(field: &str, url: Option<&str>) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let url = match url {
+        Some(url) => url,
+        None => return Ok(()),
+    };
+    let valid = url.len() <= MAX_URL_LENGTH
+        && !url.chars().any(|c| c.is_whitespace() || c.is_control())
+        && URL_SCHEMES.iter().any(|scheme| {
+            matches!(url.strip_prefix(scheme), Some(rest) if !rest.is_empty() && !rest.starts_with('/'))
+        });
+    if !valid {
+        return Err(ContractError::InvalidUrl {
+            field: field.to_string(),
+            url: url.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn burn<T, C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let token = load_token(deps.storage, &token_id)?;
+    check_can_send::<E>(deps.as_ref(), &env, &info, &token_id, &token)?;
+    check_not_frozen(deps.storage, &token_id)?;
+
+    get_tokens().remove(deps.storage, &token_id)?;
+    get_token_metadata::<T>().remove(deps.storage, &token_id);
+    BURNED_TOKENS.save(deps.storage, &token_id, &Empty {})?;
+    get_pending_transfers().remove(deps.storage, &token_id)?;
+    get_locks().remove(deps.storage, &token_id)?;
+    decrement_tokens(deps.storage)?;
+    record_burn(deps.storage, &token.owner)?;
+    append_history(
+        deps.storage,
+        &token_id,
+        &HistoryEntry::new(&env.block, Some(token.owner), None, HistoryAction::Burn),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn transfer_nft<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let fee = _transfer_nft::<E>(
+        deps,
+        &env,
+        &info,
+        &recipient,
+        &token_id,
+        HistoryAction::Transfer,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(fee)
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn send_nft<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    // Transfer token
+    let fee = _transfer_nft::<E>(deps, &env, &info, &contract, &token_id, HistoryAction::Send)?;
+
+    let send = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    };
+
+    // Send message
+    Ok(Response::new()
+        .add_message(send.into_cosmos_msg(contract.clone())?)
+        .add_messages(fee)
+        .add_attribute("action", "send_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+/// moves the token once the sender is authorized and returns the message forwarding the fee
+fn _transfer_nft<E>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &str,
+    token_id: &str,
+    action: HistoryAction,
+) -> Result<Option<BankMsg>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let mut token = load_token(deps.storage, token_id)?;
+    // ensure we have permissions
+    check_can_send(deps.as_ref(), env, info, token_id, &token)?;
+    check_not_frozen(deps.storage, token_id)?;
+    // set owner and remove existing approvals
+    let recipient = deps.api.addr_validate(recipient)?;
+    check_recipient(deps.storage, &recipient)?;
+    let fee = collect_transfer_fee(deps.storage, &info.funds)?;
+    record_transfer(deps.storage, &token.owner, &recipient)?;
+    append_history(
+        deps.storage,
+        token_id,
+        &HistoryEntry::new(
+            &env.block,
+            Some(token.owner),
+            Some(recipient.clone()),
+            action,
+        ),
+    )?;
+    token.owner = recipient;
+    token.approvals = vec![];
+    get_tokens().save(deps.storage, token_id, &token)?;
+    // offers and expired locks of the previous owner don't follow the token
+    get_pending_transfers().remove(deps.storage, token_id)?;
+    get_locks().remove(deps.storage, token_id)?;
+    Ok(fee)
+}
+
+/// allows the manager to lock and unlock the tokens of the sender
+pub fn approve_lock_manager<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    manager: String,
+    expires: Option<Expiration>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    LOCK_MANAGERS.save(deps.storage, (&info.sender, &manager_addr), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_lock_manager")
+        .add_attribute("sender", info.sender)
+        .add_attribute("manager", manager))
+}
+
+/// the locks already set by the manager stay until they expire or the manager lifts them
+pub fn revoke_lock_manager<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    manager: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    LOCK_MANAGERS.remove(deps.storage, (&info.sender, &manager_addr));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_lock_manager")
+        .add_attribute("sender", info.sender)
+        .add_attribute("manager", manager))
+}
+
+/// locks the token against transfer without moving it, called by the owner or a lock manager.
+/// A lock can only be replaced by the account that set it, until it expires
+pub fn lock_token<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let token = load_token(deps.storage, &token_id)?;
+    check_can_lock(deps.as_ref(), &env, &info, &token)?;
+    if let Some(lock) = get_locks().may_load(deps.storage, &token_id)? {
+        if lock.locked_by != info.sender && !lock.is_expired(&env.block) {
+            return Err(ContractError::TokenLocked { token_id });
+        }
+    }
+
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let lock = Lock {
+        owner: token.owner,
+        locked_by: info.sender.clone(),
+        expires,
+    };
+    get_locks().save(deps.storage, &token_id, &lock)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "lock_token")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+/// lifts the lock, only the account that set it can unlock the token before it expires
+pub fn unlock_token<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let token = load_token(deps.storage, &token_id)?;
+    match get_locks().may_load(deps.storage, &token_id)? {
+        // even a lock manager revoked since then can lift its lock, and so can the owner
+        Some(lock) if !lock.is_expired(&env.block) => {
+            let revoked = token.owner == info.sender
+                && !is_lock_manager(deps.as_ref(), &env, &token.owner, &lock.locked_by)?;
+            if lock.locked_by != info.sender && !revoked {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+        _ => check_can_lock(deps.as_ref(), &env, &info, &token)?,
+    }
+    get_locks().remove(deps.storage, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unlock_token")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+/// the owner and its lock managers can lock the token
+fn check_can_lock<E>(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token: &TokenInfo,
+) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    if token.owner == info.sender || is_lock_manager(deps, env, &token.owner, &info.sender)? {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+/// whether the manager holds an unexpired approval of the owner
+fn is_lock_manager(deps: Deps, env: &Env, owner: &Addr, manager: &Addr) -> StdResult<bool> {
+    let manager = LOCK_MANAGERS.may_load(deps.storage, (owner, manager))?;
+    Ok(matches!(manager, Some(ex) if !ex.is_expired(&env.block)))
+}
+
+/// offers the token to the recipient, who has to accept it before it moves.
+/// A new offer replaces the pending one
+pub fn offer_transfer<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let token = load_token(deps.storage, &token_id)?;
+    // the checks of _transfer_nft, so that offers which can't be accepted are rejected up front
+    check_can_send(deps.as_ref(), &env, &info, &token_id, &token)?;
+    check_not_frozen(deps.storage, &token_id)?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    check_recipient(deps.storage, &recipient_addr)?;
+    if recipient_addr == token.owner {
+        return Err(ContractError::RecipientIsOwner { token_id });
+    }
+
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let pending = PendingTransfer {
+        sender: info.sender.clone(),
+        recipient: recipient_addr,
+        expires,
+    };
+    get_pending_transfers().save(deps.storage, &token_id, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "offer_transfer")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+/// moves the token to the recipient of the pending transfer,
+/// the sender of the offer must still be able to send the token
+pub fn accept_transfer<C, E>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let pending = load_pending_transfer(deps.storage, &token_id)?;
+    if info.sender != pending.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pending.expires.is_expired(&env.block) {
+        return Err(ContractError::PendingTransferExpired { token_id });
+    }
+
+    // the recipient pays the fee of the transfer it accepts
+    let sender_info = MessageInfo {
+        sender: pending.sender.clone(),
+        funds: info.funds.clone(),
+    };
+    let fee = _transfer_nft::<E>(
+        deps,
+        &env,
+        &sender_info,
+        pending.recipient.as_str(),
+        &token_id,
+        HistoryAction::Transfer,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(fee)
+        .add_attribute("action", "accept_transfer")
+        .add_attribute("sender", pending.sender)
+        .add_attribute("recipient", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+/// drops the pending transfer, allowed for the sender of the offer and the owner
+pub fn cancel_transfer<C, E>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response<C>, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+    C: CustomMsg,
+{
+    let pending = load_pending_transfer(deps.storage, &token_id)?;
+    let token = load_token(deps.storage, &token_id)?;
+    if info.sender != pending.sender && info.sender != token.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    get_pending_transfers().remove(deps.storage, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_transfer")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _update_approvals<E>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    spender: &str,
+    token_id: &str,
+    // if add == false, remove. if add == true, remove then set with this expiration
+    add: bool,
+    expires: Option<Expiration>,
+) -> Result<TokenInfo, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let mut token = load_token(deps.storage, token_id)?;
+    // ensure we have permissions
+    check_can_approve(deps.as_ref(), env, info, token_id, &token)?;
+
+    // update the approval list (remove any for the same spender before adding)
+    let spender_addr = deps.api.addr_validate(spender)?;
+    token.approvals.retain(|apr| apr.spender != spender_addr);
+
+    // only difference between approve and revoke
+    if add {
+        check_not_frozen(deps.storage, token_id)?;
+        check_recipient(deps.storage, &spender_addr)?;
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        let approval = Approval {
+            spender: spender_addr,
+            expires,
+        };
+        token.approvals.push(approval);
+    }
+
+    get_tokens().save(deps.storage, token_id, &token)?;
+
+    Ok(token)
+}
+
+/// returns true iff the sender can execute approve or reject on the contract
+pub fn check_can_approve<E>(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    token: &TokenInfo,
+) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    // owner can approve
+    if token.owner == info.sender {
+        return Ok(());
+    }
+    // operator can approve
+    check_operator(deps, env, &token.owner, &info.sender, token_id)
+}
+
+/// returns true iff the sender can transfer ownership of the token
+pub fn check_can_send<E>(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    token: &TokenInfo,
+) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    // nobody can send a locked token
+    if let Some(lock) = get_locks().may_load(deps.storage, token_id)? {
+        if !lock.is_expired(&env.block) {
+            return Err(ContractError::TokenLocked {
+                token_id: token_id.to_string(),
+            });
+        }
+    }
+
+    // owner can send
+    if token.owner == info.sender {
+        return Ok(());
+    }
+
+    // any non-expired token approval can send
+    if token
+        .approvals
+        .iter()
+        .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+    {
+        return Ok(());
+    }
+
+    // operator can send
+    check_operator(deps, env, &token.owner, &info.sender, token_id)
+}
+
+/// operators of all the tokens of the owner, or of a scope containing the token
+fn check_operator<E>(
+    deps: Deps,
+    env: &Env,
+    owner: &Addr,
+    operator: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    let op = OPERATORS.may_load(deps.storage, (owner, operator))?;
+    if matches!(op, Some(ex) if !ex.is_expired(&env.block)) {
+        return Ok(());
+    }
+
+    let grant = SCOPED_GRANTS.may_load(deps.storage, (owner, operator))?;
+    match grant {
+        Some(grant) if !grant.is_expired(&env.block) && grant.scope.contains(token_id) => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+#[cfg(test)]
+mod execute_tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::{ContractError, CustomError};
+    use crate::msg::{
+        AddressListResponse, ClaimableResponse, CollectionInfoMsg, CollectionInfoResponse,
+        Cw20Coin, Cw20HookMsg, Cw20PricesResponse, Cw20ProceedsResponse, Cw20ReceiveMsg,
+        ExecuteMsg, FrozenTokenResponse, FrozenTokensResponse, InstantiateMsg, LockResponse,
+        LockedTokensResponse, MintMsg, PayeeMsg, PayeesResponse, PendingTransferResponse,
+        PendingTransfersResponse, QueryMsg, ScopedGrantResponse, ScopedGrantsResponse, SocialLink,
+        TransferFeeMsg, TransferFeeResponse,
+    };
+    use crate::state::{
+        get_token_metadata, get_tokens, AddressListMode, CharClass, GrantScope, TokenIdPolicy,
+        TokenMetadata, MAX_SCOPED_TOKENS,
+    };
+    use crate::testing::fixtures::{
+        approve, burn, init, mint, transfer_nft, Extension, TestExtension, ADDR1, ADDR2,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty,
+        Env, StdError, Uint128, WasmMsg,
+    };
+    use cw721::{ContractInfoResponse, Expiration, NumTokensResponse, OwnerOfResponse};
+
+    #[test]
+    fn test_execute_mint() {
+        let mut deps = mock_dependencies();
+
+        init(deps.as_mut());
+        let res = mint(deps.as_mut(), ADDR1, "1").unwrap();
+        mint(deps.as_mut(), ADDR1, "2").unwrap();
+
+        let token_1 = get_tokens().load(&deps.storage, "1").unwrap();
+        let token_1_metadata: TokenMetadata<Extension> =
+            get_token_metadata().load(&deps.storage, "1").unwrap();
+
+        assert_eq!(token_1.owner, ADDR1);
+        assert_eq!(token_1_metadata.extension, None);
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "mint"),
+                attr("minter", ADDR1),
+                attr("owner", ADDR1),
+                attr("token_id", "1"),
+            ]
+        );
+
+        let num_tokens_query_msg = QueryMsg::<Empty>::NumTokens {};
+        let num_tokens_query_res: NumTokensResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                num_tokens_query_msg,
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(num_tokens_query_res, NumTokensResponse { count: 2 });
+    }
+
+    fn query_collection_info(deps: Deps) -> CollectionInfoResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::CollectionInfo {},
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collection_info() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+
+        // empty until set, the admin defaults to the minter
+        let res = query_collection_info(deps.as_ref());
+        assert_eq!(res.admin, ADDR1);
+        assert_eq!(res.description, None);
+        assert!(res.social_links.is_empty());
+
+        let collection_info = CollectionInfoMsg {
+            description: Some("Collection of tokens".to_string()),
+            image: Some("ipfs://QmImage".to_string()),
+            banner_url: Some("https://example.com/banner.png".to_string()),
+            external_link: Some("https://example.com".to_string()),
+            creator: Some(ADDR2.to_string()),
+            social_links: vec![SocialLink {
+                name: "twitter".to_string(),
+                url: "https://twitter.com/example".to_string(),
+            }],
+        };
+        let update = |sender: &str, collection_info: CollectionInfoMsg, deps: DepsMut| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::<Extension, Empty>::UpdateCollectionInfo { collection_info },
+                &TestExtension,
+            )
+        };
+
+        let err = update(ADDR2, collection_info.clone(), deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        update(ADDR1, collection_info.clone(), deps.as_mut()).unwrap();
+        assert_eq!(
+            query_collection_info(deps.as_ref()),
+            CollectionInfoResponse {
+                name: "cw721-contract".to_string(),
+                symbol: "cw721".to_string(),
+                admin: ADDR1.to_string(),
+                description: collection_info.description.clone(),
+                image: collection_info.image.clone(),
+                banner_url: collection_info.banner_url.clone(),
+                external_link: collection_info.external_link.clone(),
+                creator: Some(ADDR2.to_string()),
+                social_links: collection_info.social_links.clone(),
+            }
+        );
+
+        // ContractInfo is unchanged
+        let contract_info: ContractInfoResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ContractInfo {},
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            contract_info,
+            ContractInfoResponse {
+                name: "cw721-contract".to_string(),
+                symbol: "cw721".to_string(),
+            }
+        );
+
+        for (field, url) in [
+            ("image", "example.com/image.png"),
+            ("banner_url", "https://"),
+            ("external_link", "https://example.com/a b"),
+            ("twitter", "javascript:alert(1)"),
+        ] {
+            let mut invalid = collection_info.clone();
+            match field {
+                "image" => invalid.image = Some(url.to_string()),
+                "banner_url" => invalid.banner_url = Some(url.to_string()),
+                "external_link" => invalid.external_link = Some(url.to_string()),
+                _ => invalid.social_links[0].url = url.to_string(),
+            }
+            let err = update(ADDR1, invalid, deps.as_mut()).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidUrl {
+                    field: field.to_string(),
+                    url: url.to_string(),
+                }
+            );
+            assert_eq!(err.code(), "invalid_url");
+        }
+
+        // set at instantiate, with another admin
+        let mut deps = mock_dependencies();
+        instantiate::<Extension, Empty, CustomError>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            InstantiateMsg {
+                minter: ADDR1.to_string(),
+                admin: Some(ADDR2.to_string()),
+                collection_info: Some(collection_info.clone()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let res = query_collection_info(deps.as_ref());
+        assert_eq!(res.admin, ADDR2);
+        assert_eq!(res.image, collection_info.image);
+        let err = update(ADDR1, CollectionInfoMsg::default(), deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        update(ADDR2, CollectionInfoMsg::default(), deps.as_mut()).unwrap();
+        assert_eq!(query_collection_info(deps.as_ref()).image, None);
+    }
+
+    #[test]
+    fn test_token_id_policy() {
+        // the default policy rejects empty, blank and oversized ids
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "").unwrap_err(),
+            ContractError::TokenIdEmpty {}
+        );
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "token 1").unwrap_err(),
+            ContractError::TokenIdInvalidChar {
+                token_id: "token 1".to_string(),
+                invalid: ' ',
+            }
+        );
+        let err = mint(deps.as_mut(), ADDR1, &"1".repeat(257)).unwrap_err();
+        assert_eq!(err, ContractError::TokenIdTooLong { max_length: 256 });
+        assert_eq!(err.code(), "token_id_too_long");
+        // any Unicode character is accepted by default, as cw721-base does
+        mint(deps.as_mut(), ADDR1, "Ünïcode").unwrap();
+
+        let init_with = |policy: TokenIdPolicy| {
+            let mut deps = mock_dependencies();
+            instantiate::<Extension, Empty, CustomError>(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADDR1, &[]),
+                InstantiateMsg {
+                    minter: ADDR1.to_string(),
+                    token_id_policy: Some(policy),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            deps
+        };
+
+        let mut deps = init_with(TokenIdPolicy {
+            max_length: 8,
+            allowed_chars: Some(vec![CharClass::Lowercase, CharClass::Punctuation]),
+            numeric_only: false,
+            case_folding: true,
+        });
+        // folded before the hooks and the claimed check
+        let res = mint(deps.as_mut(), ADDR1, "Token-a").unwrap();
+        assert_eq!(res.attributes[3], attr("token_id", "token-a"));
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "TOKEN-A").unwrap_err(),
+            ContractError::Claimed {}
+        );
+        // every message and query naming the token is folded too
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: ADDR2.to_string(),
+                token_id: "TOKEN-A".to_string(),
+            },
+            &TestExtension,
+        )
+        .unwrap();
+        let owner: OwnerOfResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: "Token-A".to_string(),
+                    include_expired: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, ADDR2);
+        // the minter is checked before the policy
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR2, &[]),
+            ExecuteMsg::Mint(MintMsg {
+                token_id: "token-1".to_string(),
+                owner: ADDR2.to_string(),
+                token_uri: None,
+                extension: None,
+            }),
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "token-1").unwrap_err(),
+            ContractError::TokenIdInvalidChar {
+                token_id: "token-1".to_string(),
+                invalid: '1',
+            }
+        );
+        // Cyrillic "а" looks like the Latin one
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "token-\u{430}").unwrap_err(),
+            ContractError::TokenIdInvalidChar {
+                token_id: "token-\u{430}".to_string(),
+                invalid: '\u{430}',
+            }
+        );
+        assert_eq!(
+            mint(deps.as_mut(), ADDR1, "token-abc").unwrap_err(),
+            ContractError::TokenIdTooLong { max_length: 8 }
+        );
+
+        let mut deps = init_with(TokenIdPolicy {
+            numeric_only: true,
+            ..TokenIdPolicy::default()
+        });
+        mint(deps.as_mut(), ADDR1, "0").unwrap();
+        mint(deps.as_mut(), ADDR1, "10").unwrap();
+        for token_id in ["01", "1a", "-1", "１"] {
+            assert_eq!(
+                mint(deps.as_mut(), ADDR1, token_id).unwrap_err(),
+                ContractError::TokenIdNotNumeric {
+                    token_id: token_id.to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_transfer_keeps_metadata() {
+        let mut deps = mock_dependencies();
+
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        let metadata_before: TokenMetadata<Extension> =
+            get_token_metadata().load(&deps.storage, "1").unwrap();
+
+        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
+
+        // ownership record changed, metadata record is left as minted
+        assert_eq!(get_tokens().load(&deps.storage, "1").unwrap().owner, ADDR2);
+        let metadata_after: TokenMetadata<Extension> =
+            get_token_metadata().load(&deps.storage, "1").unwrap();
+        assert_eq!(metadata_before, metadata_after);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut deps = mock_dependencies();
+
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        let burn_msg = ExecuteMsg::Burn {
+            token_id: "1".to_string(),
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            burn_msg.clone(),
+            &TestExtension,
+        )
+        .unwrap();
+        // Cannot burn same nft again
+        let burn_err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            burn_msg,
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(
+            burn_err,
+            ContractError::TokenNotFound {
+                token_id: "1".to_string()
+            }
+        );
+        assert_eq!(burn_err.code(), "token_not_found");
+
+        // metadata is gone with the token
+        let nft_info_err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo {
+                token_id: "1".to_string(),
+            },
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(
+            nft_info_err,
+            StdError::not_found("cw721_simple_base::state::TokenInfo")
+        );
+    }
+
+    #[test]
+    fn test_two_step_transfer() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        let run = |deps: DepsMut, sender: &str, msg: ExecuteMsg<Extension, Empty>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                msg,
+                &TestExtension,
+            )
+        };
+        let offer = |expires: Option<Expiration>| ExecuteMsg::OfferTransfer {
+            recipient: ADDR2.to_string(),
+            token_id: "1".to_string(),
+            expires,
+        };
+        let accept = ExecuteMsg::AcceptTransfer {
+            token_id: "1".to_string(),
+        };
+        let cancel = ExecuteMsg::CancelTransfer {
+            token_id: "1".to_string(),
+        };
+        let query_owner = |deps: Deps| -> String {
+            let msg = QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            };
+            let res: cw721::OwnerOfResponse =
+                from_binary(&query(deps, mock_env(), msg, &TestExtension).unwrap()).unwrap();
+            res.owner
+        };
+
+        // only those who can send the token can offer it
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, offer(None)).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, offer(Some(Expiration::AtHeight(1)))).unwrap_err(),
+            ContractError::Expired {}
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, accept.clone()).unwrap_err(),
+            ContractError::PendingTransferNotFound {
+                token_id: "1".to_string()
+            }
+        );
+
+        // offers that could never be accepted are rejected
+        let self_offer = ExecuteMsg::OfferTransfer {
+            recipient: ADDR1.to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        };
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, self_offer).unwrap_err(),
+            ContractError::RecipientIsOwner {
+                token_id: "1".to_string()
+            }
+        );
+        let block = ExecuteMsg::UpdateAddressList {
+            mode: None,
+            add: vec![ADDR2.to_string()],
+            remove: vec![],
+        };
+        run(deps.as_mut(), ADDR1, block).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, offer(None)).unwrap_err().code(),
+            "address_blocked"
+        );
+        let unblock = ExecuteMsg::UpdateAddressList {
+            mode: None,
+            add: vec![],
+            remove: vec![ADDR2.to_string()],
+        };
+        run(deps.as_mut(), ADDR1, unblock).unwrap();
+        let freeze = ExecuteMsg::FreezeToken {
+            token_id: "1".to_string(),
+            reason: "dispute".to_string(),
+        };
+        run(deps.as_mut(), ADDR1, freeze).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, offer(None)).unwrap_err().code(),
+            "token_frozen"
+        );
+        let unfreeze = ExecuteMsg::UnfreezeToken {
+            token_id: "1".to_string(),
+        };
+        run(deps.as_mut(), ADDR1, unfreeze).unwrap();
+
+        // the token stays with the owner until the recipient accepts
+        run(deps.as_mut(), ADDR1, offer(None)).unwrap();
+        assert_eq!(query_owner(deps.as_ref()), ADDR1);
+        let pending: PendingTransfersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PendingTransfers {
+                    recipient: ADDR2.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            pending.transfers,
+            vec![PendingTransferResponse {
+                token_id: "1".to_string(),
+                sender: ADDR1.to_string(),
+                recipient: ADDR2.to_string(),
+                expires: Expiration::Never {},
+            }]
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, accept.clone()).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        run(deps.as_mut(), ADDR2, accept.clone()).unwrap();
+        assert_eq!(query_owner(deps.as_ref()), ADDR2);
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, accept.clone()).unwrap_err(),
+            ContractError::PendingTransferNotFound {
+                token_id: "1".to_string()
+            }
+        );
+
+        // cancelled and expired offers can't be accepted
+        let recipient_offer = ExecuteMsg::OfferTransfer {
+            recipient: ADDR1.to_string(),
+            token_id: "1".to_string(),
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 1)),
+        };
+        run(deps.as_mut(), ADDR2, recipient_offer.clone()).unwrap();
+        run(deps.as_mut(), ADDR2, cancel.clone()).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, accept.clone()).unwrap_err(),
+            ContractError::PendingTransferNotFound {
+                token_id: "1".to_string()
+            }
+        );
+        run(deps.as_mut(), ADDR2, recipient_offer).unwrap();
+        let mut env = mock_env();
+        env.block.height += 1;
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ADDR1, &[]),
+            accept.clone(),
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "pending_transfer_expired");
+        // expired offers are listed on request only
+        let pending_to_addr1 = |include_expired: bool| {
+            let mut env = mock_env();
+            env.block.height += 1;
+            let res: PendingTransfersResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    env,
+                    QueryMsg::PendingTransfers {
+                        recipient: ADDR1.to_string(),
+                        include_expired: Some(include_expired),
+                        start_after: None,
+                        limit: None,
+                    },
+                    &TestExtension,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.transfers.len()
+        };
+        assert_eq!(pending_to_addr1(false), 0);
+        assert_eq!(pending_to_addr1(true), 1);
+
+        // a direct transfer drops the offer of the previous owner
+        transfer_nft(deps.as_mut(), ADDR2, ADDR1).unwrap();
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PendingTransfer {
+                token_id: "1".to_string(),
+            },
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("pending_transfer_not_found: No pending transfer for: 1")
+        );
+    }
+
+    #[test]
+    fn test_scoped_grants() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        for token_id in ["gold-1", "gold-2", "silver-1"] {
+            mint(deps.as_mut(), ADDR1, token_id).unwrap();
+        }
+        let run = |deps: DepsMut, sender: &str, msg: ExecuteMsg<Extension, Empty>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                msg,
+                &TestExtension,
+            )
+        };
+        let grant = |scope: GrantScope| ExecuteMsg::ApproveScoped {
+            operator: ADDR2.to_string(),
+            scope,
+            expires: None,
+        };
+        let send = |token_id: &str| ExecuteMsg::TransferNft {
+            recipient: ADDR2.to_string(),
+            token_id: token_id.to_string(),
+        };
+
+        assert_eq!(
+            run(
+                deps.as_mut(),
+                ADDR1,
+                grant(GrantScope::Prefix {
+                    prefix: "".to_string()
+                })
+            )
+            .unwrap_err(),
+            ContractError::EmptyScope {}
+        );
+        let too_many = (0..=MAX_SCOPED_TOKENS).map(|i| i.to_string()).collect();
+        assert_eq!(
+            run(
+                deps.as_mut(),
+                ADDR1,
+                grant(GrantScope::Tokens {
+                    token_ids: too_many
+                })
+            )
+            .unwrap_err(),
+            ContractError::ScopeTooLarge {
+                max: MAX_SCOPED_TOKENS
+            }
+        );
+
+        // the operator can approve and send the tokens in the scope only
+        run(
+            deps.as_mut(),
+            ADDR1,
+            grant(GrantScope::Prefix {
+                prefix: "gold-".to_string(),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, send("silver-1")).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let approve = ExecuteMsg::Approve {
+            spender: ADDR2.to_string(),
+            token_id: "gold-2".to_string(),
+            expires: None,
+        };
+        run(deps.as_mut(), ADDR2, approve).unwrap();
+        run(deps.as_mut(), ADDR2, send("gold-1")).unwrap();
+
+        // a new grant replaces the previous one
+        run(
+            deps.as_mut(),
+            ADDR1,
+            grant(GrantScope::Tokens {
+                token_ids: vec!["silver-1".to_string()],
+            }),
+        )
+        .unwrap();
+        let grants: ScopedGrantsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ScopedGrants {
+                    owner: ADDR1.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            grants.grants,
+            vec![ScopedGrantResponse {
+                operator: ADDR2.to_string(),
+                scope: GrantScope::Tokens {
+                    token_ids: vec!["silver-1".to_string()]
+                },
+                expires: Expiration::Never {},
+            }]
+        );
+
+        // expired and revoked grants give no access
+        let expiring = ExecuteMsg::ApproveScoped {
+            operator: ADDR2.to_string(),
+            scope: GrantScope::Tokens {
+                token_ids: vec!["silver-1".to_string()],
+            },
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 1)),
+        };
+        run(deps.as_mut(), ADDR1, expiring).unwrap();
+        let mut env = mock_env();
+        env.block.height += 1;
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            send("silver-1"),
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ScopedGrant {
+                owner: ADDR1.to_string(),
+                operator: ADDR2.to_string(),
+                include_expired: None,
+            },
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::not_found("Approval not found"));
+
+        let revoke = ExecuteMsg::RevokeScoped {
+            operator: ADDR2.to_string(),
+        };
+        run(deps.as_mut(), ADDR1, revoke).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, send("silver-1")).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+    }
+
+    #[test]
+    fn test_compliance() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        mint(deps.as_mut(), ADDR1, "2").unwrap();
+        let run = |deps: DepsMut, sender: &str, msg: ExecuteMsg<Extension, Empty>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                msg,
+                &TestExtension,
+            )
+        };
+        let update_list = |mode: Option<AddressListMode>, add: &[&str], remove: &[&str]| {
+            ExecuteMsg::UpdateAddressList {
+                mode,
+                add: add.iter().map(|a| a.to_string()).collect(),
+                remove: remove.iter().map(|a| a.to_string()).collect(),
+            }
+        };
+        let approve = ExecuteMsg::Approve {
+            spender: ADDR2.to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        };
+        let burn_msg = ExecuteMsg::Burn {
+            token_id: "2".to_string(),
+        };
+
+        // only the admin manages the list and the freezes
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, update_list(None, &[ADDR2], &[])).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let freeze = ExecuteMsg::FreezeToken {
+            token_id: "2".to_string(),
+            reason: "disputed sale".to_string(),
+        };
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, freeze.clone()).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        // blocked addresses can't receive tokens or approvals
+        run(deps.as_mut(), ADDR1, update_list(None, &[ADDR2], &[])).unwrap();
+        let blocked = ContractError::AddressBlocked {
+            address: ADDR2.to_string(),
+        };
+        assert_eq!(
+            transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap_err(),
+            blocked
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, approve.clone()).unwrap_err(),
+            blocked
+        );
+        assert_eq!(mint(deps.as_mut(), ADDR2, "3").unwrap_err(), blocked);
+
+        // with an allowlist only the listed addresses can
+        run(
+            deps.as_mut(),
+            ADDR1,
+            update_list(Some(AddressListMode::Allowlist), &[], &[]),
+        )
+        .unwrap();
+        run(deps.as_mut(), ADDR1, approve).unwrap();
+        run(deps.as_mut(), ADDR1, update_list(None, &[], &[ADDR2])).unwrap();
+        assert_eq!(
+            transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap_err(),
+            blocked
+        );
+        run(
+            deps.as_mut(),
+            ADDR1,
+            update_list(Some(AddressListMode::Blocklist), &[], &[]),
+        )
+        .unwrap();
+        let list: AddressListResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AddressList {
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(list.mode, AddressListMode::Blocklist);
+        assert!(list.addresses.is_empty());
+
+        // frozen tokens can't be sent, approved or burned
+        let res = run(deps.as_mut(), ADDR1, freeze).unwrap();
+        assert_eq!(res.attributes[3], attr("reason", "disputed sale"));
+        let frozen = ContractError::TokenFrozen {
+            token_id: "2".to_string(),
+            reason: "disputed sale".to_string(),
+        };
+        let transfer_2 = ExecuteMsg::TransferNft {
+            recipient: ADDR2.to_string(),
+            token_id: "2".to_string(),
+        };
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, transfer_2.clone()).unwrap_err(),
+            frozen
+        );
+        let approve_2 = ExecuteMsg::Approve {
+            spender: ADDR2.to_string(),
+            token_id: "2".to_string(),
+            expires: None,
+        };
+        assert_eq!(run(deps.as_mut(), ADDR1, approve_2).unwrap_err(), frozen);
+        let err = run(deps.as_mut(), ADDR1, burn_msg).unwrap_err();
+        assert_eq!(err, frozen);
+        assert_eq!(err.code(), "token_frozen");
+
+        let frozen_tokens: FrozenTokensResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::FrozenTokens {
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            frozen_tokens.tokens,
+            vec![FrozenTokenResponse {
+                token_id: "2".to_string(),
+                reason: "disputed sale".to_string(),
+            }]
+        );
+
+        let unfreeze = ExecuteMsg::UnfreezeToken {
+            token_id: "2".to_string(),
+        };
+        run(deps.as_mut(), ADDR1, unfreeze).unwrap();
+        run(deps.as_mut(), ADDR1, transfer_2).unwrap();
+        let unfreeze_missing = ExecuteMsg::UnfreezeToken {
+            token_id: "missing".to_string(),
+        };
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, unfreeze_missing).unwrap_err(),
+            ContractError::TokenNotFound {
+                token_id: "missing".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_locks() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        mint(deps.as_mut(), ADDR1, "2").unwrap();
+        let run = |deps: DepsMut, env: Env, sender: &str, msg: ExecuteMsg<Extension, Empty>| {
+            execute(deps, env, mock_info(sender, &[]), msg, &TestExtension)
+        };
+        let lock = |token_id: &str, expires: Option<Expiration>| ExecuteMsg::LockToken {
+            token_id: token_id.to_string(),
+            expires,
+        };
+        let unlock = |token_id: &str| ExecuteMsg::UnlockToken {
+            token_id: token_id.to_string(),
+        };
+        let transfer = |token_id: &str| ExecuteMsg::TransferNft {
+            recipient: ADDR2.to_string(),
+            token_id: token_id.to_string(),
+        };
+        let locked_tokens = |deps: Deps, env: Env, include_expired: bool| {
+            let res: LockedTokensResponse = from_binary(
+                &query(
+                    deps,
+                    env,
+                    QueryMsg::LockedTokens {
+                        owner: ADDR1.to_string(),
+                        include_expired: Some(include_expired),
+                        start_after: None,
+                        limit: None,
+                    },
+                    &TestExtension,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.tokens
+        };
+        let expires = Expiration::AtHeight(mock_env().block.height + 100);
+        let mut later = mock_env();
+        later.block.height += 100;
+
+        // only the owner and its lock managers can lock
+        assert_eq!(
+            run(deps.as_mut(), mock_env(), ADDR2, lock("1", Some(expires))).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let approve_manager = ExecuteMsg::ApproveLockManager {
+            manager: ADDR2.to_string(),
+            expires: None,
+        };
+        run(deps.as_mut(), mock_env(), ADDR1, approve_manager).unwrap();
+        run(deps.as_mut(), mock_env(), ADDR2, lock("1", Some(expires))).unwrap();
+        run(deps.as_mut(), mock_env(), ADDR1, lock("2", None)).unwrap();
+
+        // a locked token can't be sent or burned, even by its owner
+        let locked = ContractError::TokenLocked {
+            token_id: "1".to_string(),
+        };
+        let err = run(deps.as_mut(), mock_env(), ADDR1, transfer("1")).unwrap_err();
+        assert_eq!(err, locked);
+        assert_eq!(err.code(), "token_locked");
+        assert_eq!(burn(deps.as_mut(), "1").unwrap_err(), locked);
+
+        // only the account that set the lock can lift it or take it over
+        assert_eq!(
+            run(deps.as_mut(), mock_env(), ADDR1, unlock("1")).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            run(deps.as_mut(), mock_env(), ADDR1, lock("1", None)).unwrap_err(),
+            locked
+        );
+        assert_eq!(
+            run(deps.as_mut(), mock_env(), ADDR2, unlock("2")).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        assert_eq!(
+            locked_tokens(deps.as_ref(), mock_env(), false),
+            vec![
+                LockResponse {
+                    token_id: "1".to_string(),
+                    locked_by: ADDR2.to_string(),
+                    expires,
+                },
+                LockResponse {
+                    token_id: "2".to_string(),
+                    locked_by: ADDR1.to_string(),
+                    expires: Expiration::Never {},
+                },
+            ]
+        );
+        assert_eq!(locked_tokens(deps.as_ref(), later.clone(), false).len(), 1);
+        assert_eq!(locked_tokens(deps.as_ref(), later.clone(), true).len(), 2);
+
+        // the expired lock no longer holds the token, and is cleared by the transfer
+        run(deps.as_mut(), later.clone(), ADDR1, transfer("1")).unwrap();
+        assert_eq!(locked_tokens(deps.as_ref(), later, true).len(), 1);
+
+        run(deps.as_mut(), mock_env(), ADDR1, unlock("2")).unwrap();
+        run(deps.as_mut(), mock_env(), ADDR1, transfer("2")).unwrap();
+        assert!(locked_tokens(deps.as_ref(), mock_env(), true).is_empty());
+
+        // the owner lifts the locks of a manager it revoked
+        mint(deps.as_mut(), ADDR1, "3").unwrap();
+        run(deps.as_mut(), mock_env(), ADDR2, lock("3", None)).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), mock_env(), ADDR1, unlock("3")).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let revoke_manager = ExecuteMsg::RevokeLockManager {
+            manager: ADDR2.to_string(),
+        };
+        run(deps.as_mut(), mock_env(), ADDR1, revoke_manager).unwrap();
+        run(deps.as_mut(), mock_env(), ADDR1, unlock("3")).unwrap();
+        run(deps.as_mut(), mock_env(), ADDR1, transfer("3")).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_fee() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        let run = |deps: DepsMut, sender: &str, funds: &[Coin], msg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, funds),
+                msg,
+                &TestExtension,
+            )
+        };
+        let transfer_fee = TransferFeeMsg {
+            amount: coin(100, "ujuno"),
+            treasury: "treasury".to_string(),
+        };
+        let update = |transfer_fee: Option<TransferFeeMsg>| {
+            ExecuteMsg::<Extension, Empty>::UpdateTransferFee { transfer_fee }
+        };
+        let transfer = |recipient: &str| ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: "1".to_string(),
+        };
+
+        // only the admin sets the fee, and it can't be zero
+        assert_eq!(
+            run(
+                deps.as_mut(),
+                ADDR2,
+                &[],
+                update(Some(transfer_fee.clone()))
+            )
+            .unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let zero_fee = TransferFeeMsg {
+            amount: coin(0, "ujuno"),
+            ..transfer_fee.clone()
+        };
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, &[], update(Some(zero_fee))).unwrap_err(),
+            ContractError::ZeroTransferFee {}
+        );
+        run(
+            deps.as_mut(),
+            ADDR1,
+            &[],
+            update(Some(transfer_fee.clone())),
+        )
+        .unwrap();
+        let res: TransferFeeResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TransferFee {},
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.transfer_fee, Some(transfer_fee));
+
+        // the sender is authorized before the fee is checked
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, &[], transfer(ADDR2)).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        let err = run(deps.as_mut(), ADDR1, &[], transfer(ADDR2)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongFeeAmount {
+                required: coin(100, "ujuno"),
+                paid: coin(0, "ujuno"),
+            }
+        );
+        assert_eq!(err.code(), "wrong_fee_amount");
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, &coins(60, "ujuno"), transfer(ADDR2)).unwrap_err(),
+            ContractError::WrongFeeAmount {
+                required: coin(100, "ujuno"),
+                paid: coin(60, "ujuno"),
+            }
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, &coins(150, "ujuno"), transfer(ADDR2)).unwrap_err(),
+            ContractError::WrongFeeAmount {
+                required: coin(100, "ujuno"),
+                paid: coin(150, "ujuno"),
+            }
+        );
+        let wrong_denom = [coin(100, "ujuno"), coin(5, "uatom")];
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, &wrong_denom, transfer(ADDR2)).unwrap_err(),
+            ContractError::WrongFeeDenom {
+                expected: "ujuno".to_string(),
+                denom: "uatom".to_string(),
+            }
+        );
+
+        // the fee is forwarded to the treasury
+        let res = run(deps.as_mut(), ADDR1, &coins(100, "ujuno"), transfer(ADDR2)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(100, "ujuno"),
+            }
+            .into()
+        );
+
+        // transfers are free again once removed
+        run(deps.as_mut(), ADDR1, &[], update(None)).unwrap();
+        let res = run(deps.as_mut(), ADDR2, &[], transfer(ADDR1)).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_cw20_mint() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        let run = |deps: DepsMut, sender: &str, msg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                msg,
+                &TestExtension,
+            )
+        };
+        let set_price = |price: Option<u128>| ExecuteMsg::<Extension, Empty>::UpdateCw20Price {
+            token: "cw20token".to_string(),
+            price: price.map(Uint128::new),
+        };
+        let receive = |amount: u128, token_id: &str, owner: Option<&str>| {
+            let mint = Cw20HookMsg::<Extension>::Mint {
+                token_id: token_id.to_string(),
+                owner: owner.map(String::from),
+                token_uri: None,
+                extension: None,
+            };
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ADDR2.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&mint).unwrap(),
+            })
+        };
+
+        // only the admin sets prices, and they can't be zero
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, set_price(Some(100))).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            run(deps.as_mut(), ADDR1, set_price(Some(0))).unwrap_err(),
+            ContractError::ZeroPrice {}
+        );
+        run(deps.as_mut(), ADDR1, set_price(Some(100))).unwrap();
+
+        // only accepted cw20 contracts can pay, with the exact price
+        let err = run(deps.as_mut(), "othertoken", receive(100, "1", None)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnsupportedCw20 {
+                address: "othertoken".to_string(),
+            }
+        );
+        assert_eq!(err.code(), "unsupported_cw20");
+        assert_eq!(
+            run(deps.as_mut(), "cw20token", receive(99, "1", None)).unwrap_err(),
+            ContractError::WrongCw20Amount {
+                price: Uint128::new(100),
+                amount: Uint128::new(99),
+            }
+        );
+
+        // the token goes to the sender of the cw20 transfer, or the chosen owner
+        let res = run(deps.as_mut(), "cw20token", receive(100, "1", None)).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "cw20_mint"));
+        assert_eq!(get_tokens().load(&deps.storage, "1").unwrap().owner, ADDR2);
+        run(deps.as_mut(), "cw20token", receive(100, "2", Some(ADDR1))).unwrap();
+        assert_eq!(get_tokens().load(&deps.storage, "2").unwrap().owner, ADDR1);
+
+        let prices: Cw20PricesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw20Prices {
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let proceeds: Cw20ProceedsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw20Proceeds {
+                    start_after: None,
+                    limit: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let cw20_coin = |amount: u128| Cw20Coin {
+            address: "cw20token".to_string(),
+            amount: Uint128::new(amount),
+        };
+        assert_eq!(prices.prices, vec![cw20_coin(100)]);
+        assert_eq!(proceeds.proceeds, vec![cw20_coin(200)]);
+
+        // removing the price stops the sales, the proceeds stay
+        run(deps.as_mut(), ADDR1, set_price(None)).unwrap();
+        assert_eq!(
+            run(deps.as_mut(), "cw20token", receive(100, "3", None)).unwrap_err(),
+            ContractError::UnsupportedCw20 {
+                address: "cw20token".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_revenue_split() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        let run = |deps: DepsMut, sender: &str, funds: &[Coin], msg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, funds),
+                msg,
+                &TestExtension,
+            )
+        };
+        let paid_mint = |token_id: &str| ExecuteMsg::<Extension, Empty>::PaidMint {
+            token_id: token_id.to_string(),
+            owner: None,
+            token_uri: None,
+            extension: None,
+        };
+        let payees = |shares: &[(&str, u16)]| ExecuteMsg::UpdatePayees {
+            payees: shares
+                .iter()
+                .map(|(address, shares_bps)| PayeeMsg {
+                    address: address.to_string(),
+                    shares_bps: *shares_bps,
+                })
+                .collect(),
+        };
+        let claimable = |deps: Deps, payee: &str| -> ClaimableResponse {
+            from_binary(
+                &query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::Claimable {
+                        payee: payee.to_string(),
+                    },
+                    &TestExtension,
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // paid mints are disabled until the admin sets a price
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, &coins(101, "ujuno"), paid_mint("1")).unwrap_err(),
+            ContractError::PaidMintDisabled {}
+        );
+        let set_price = ExecuteMsg::UpdateNativePrice {
+            price: Some(coin(101, "ujuno")),
+        };
+        run(deps.as_mut(), ADDR1, &[], set_price).unwrap();
+        let err = run(deps.as_mut(), ADDR2, &coins(100, "ujuno"), paid_mint("1")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongPaymentAmount {
+                price: coin(101, "ujuno"),
+                paid: coin(100, "ujuno"),
+            }
+        );
+        assert_eq!(err.code(), "wrong_payment_amount");
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, &coins(101, "uatom"), paid_mint("1")).unwrap_err(),
+            ContractError::WrongPaymentDenom {
+                expected: "ujuno".to_string(),
+                denom: "uatom".to_string(),
+            }
+        );
+
+        // the admin receives the revenue while no payees are set
+        run(deps.as_mut(), ADDR2, &coins(101, "ujuno"), paid_mint("1")).unwrap();
+        assert_eq!(get_tokens().load(&deps.storage, "1").unwrap().owner, ADDR2);
+        assert_eq!(claimable(deps.as_ref(), ADDR1).native, coins(101, "ujuno"));
+
+        // shares must be valid and add up to 10000 basis points
+        assert_eq!(
+            run(deps.as_mut(), ADDR2, &[], payees(&[("artist", 10_000)])).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            run(
+                deps.as_mut(),
+                ADDR1,
+                &[],
+                payees(&[("artist", 5_000), ("platform", 2_500)])
+            )
+            .unwrap_err(),
+            ContractError::InvalidShares { total: 7_500 }
+        );
+        assert_eq!(
+            run(
+                deps.as_mut(),
+                ADDR1,
+                &[],
+                payees(&[("artist", 5_000), ("artist", 5_000)])
+            )
+            .unwrap_err(),
+            ContractError::DuplicatePayee {
+                address: "artist".to_string(),
+            }
+        );
+        let split = payees(&[("artist", 5_000), ("platform", 2_500), ("dao", 2_500)]);
+        run(deps.as_mut(), ADDR1, &[], split).unwrap();
+        let res: PayeesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Payees {},
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.payees.len(), 3);
+
+        // native and cw20 revenue is split, the rounding dust goes to the first payee
+        run(deps.as_mut(), ADDR2, &coins(101, "ujuno"), paid_mint("2")).unwrap();
+        let set_cw20_price = ExecuteMsg::UpdateCw20Price {
+            token: "cw20token".to_string(),
+            price: Some(Uint128::new(40)),
+        };
+        run(deps.as_mut(), ADDR1, &[], set_cw20_price).unwrap();
+        let mint = Cw20HookMsg::<Extension>::Mint {
+            token_id: "3".to_string(),
+            owner: None,
+            token_uri: None,
+            extension: None,
+        };
+        let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ADDR2.to_string(),
+            amount: Uint128::new(40),
+            msg: to_binary(&mint).unwrap(),
+        });
+        run(deps.as_mut(), "cw20token", &[], receive).unwrap();
+
+        let artist = claimable(deps.as_ref(), "artist");
+        assert_eq!(artist.native, coins(51, "ujuno"));
+        assert_eq!(
+            artist.cw20,
+            vec![Cw20Coin {
+                address: "cw20token".to_string(),
+                amount: Uint128::new(20),
+            }]
+        );
+        assert_eq!(claimable(deps.as_ref(), "dao").native, coins(25, "ujuno"));
+        // the revenue credited before the payees were set stays with the admin
+        assert_eq!(claimable(deps.as_ref(), ADDR1).native, coins(101, "ujuno"));
+
+        // withdraw sends everything credited to the payee
+        let res = run(deps.as_mut(), "artist", &[], ExecuteMsg::Withdraw {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "artist".to_string(),
+                amount: coins(51, "ujuno"),
+            }
+            .into()
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "cw20token".to_string(),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                    recipient: "artist".to_string(),
+                    amount: Uint128::new(20),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            run(deps.as_mut(), "artist", &[], ExecuteMsg::Withdraw {}).unwrap_err(),
+            ContractError::NothingToWithdraw {}
+        );
+        let artist = claimable(deps.as_ref(), "artist");
+        assert!(artist.native.is_empty() && artist.cw20.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod invariant_tests {
+    use crate::contract::{execute, instantiate};
+    use crate::error::{ContractError, CustomError};
+    use crate::execute::{check_can_approve, check_can_send};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
+    use crate::state::{get_stats, get_tokens, token_count, TokenInfo};
+    use crate::testing::fixtures::TestExtension;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{Deps, Empty, Order};
+    use proptest::prelude::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    const ACCOUNTS: [&str; 4] = ["addr0", "addr1", "addr2", "addr3"];
+    const MINTER: &str = ACCOUNTS[0];
+    const TOKEN_IDS: usize = 6;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Mint {
+            sender: usize,
+            owner: usize,
+            token: usize,
+        },
+        Transfer {
+            sender: usize,
+            recipient: usize,
+            token: usize,
+        },
+        Approve {
+            sender: usize,
+            spender: usize,
+            token: usize,
+        },
+        Revoke {
+            sender: usize,
+            spender: usize,
+            token: usize,
+        },
+        ApproveAll {
+            sender: usize,
+            operator: usize,
+        },
+        RevokeAll {
+            sender: usize,
+            operator: usize,
+        },
+        Burn {
+            sender: usize,
+            token: usize,
+        },
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let account = 0..ACCOUNTS.len();
+        let token = 0..TOKEN_IDS;
+        prop_oneof![
+            (account.clone(), account.clone(), token.clone()).prop_map(|(sender, owner, token)| {
+                Op::Mint {
+                    sender,
+                    owner,
+                    token,
+                }
+            }),
+            (account.clone(), account.clone(), token.clone()).prop_map(
+                |(sender, recipient, token)| Op::Transfer {
+                    sender,
+                    recipient,
+                    token
+                }
+            ),
+            (account.clone(), account.clone(), token.clone()).prop_map(
+                |(sender, spender, token)| Op::Approve {
+                    sender,
+                    spender,
+                    token
+                }
+            ),
+            (account.clone(), account.clone(), token.clone()).prop_map(
+                |(sender, spender, token)| Op::Revoke {
+                    sender,
+                    spender,
+                    token
+                }
+            ),
+            (account.clone(), account.clone())
+                .prop_map(|(sender, operator)| Op::ApproveAll { sender, operator }),
+            (account.clone(), account.clone())
+                .prop_map(|(sender, operator)| Op::RevokeAll { sender, operator }),
+            (account, token).prop_map(|(sender, token)| Op::Burn { sender, token }),
+        ]
+    }
+
+    /// expected ownership state, approvals and operators never expire in these sequences
+    #[derive(Default)]
+    struct Model {
+        // token_id -> (owner, approved spenders)
+        tokens: BTreeMap<String, (usize, BTreeSet<usize>)>,
+        // (owner, operator)
+        operators: BTreeSet<(usize, usize)>,
+        burned: BTreeSet<String>,
+    }
+
+    impl Model {
+        fn can_approve(&self, sender: usize, owner: usize) -> bool {
+            sender == owner || self.operators.contains(&(owner, sender))
+        }
+
+        fn can_send(&self, sender: usize, token_id: &str) -> bool {
+            let (owner, approvals) = &self.tokens[token_id];
+            self.can_approve(sender, *owner) || approvals.contains(&sender)
+        }
+    }
+
+    enum Expected {
+        Ok,
+        Unauthorized,
+        Failed,
+    }
+
+    fn token_id(token: usize) -> String {
+        format!("token{}", token)
+    }
+
+    /// predicts the result of the op and applies it to the model when it succeeds
+    fn apply(model: &mut Model, op: &Op) -> Expected {
+        match op {
+            Op::Mint {
+                sender,
+                owner,
+                token,
+            } => {
+                if ACCOUNTS[*sender] != MINTER {
+                    return Expected::Unauthorized;
+                }
+                if model.tokens.contains_key(&token_id(*token))
+                    || model.burned.contains(&token_id(*token))
+                {
+                    return Expected::Failed;
+                }
+                model
+                    .tokens
+                    .insert(token_id(*token), (*owner, BTreeSet::new()));
+            }
+            Op::Transfer {
+                sender,
+                recipient,
+                token,
+            } => {
+                let token_id = token_id(*token);
+                if !model.tokens.contains_key(&token_id) {
+                    return Expected::Failed;
+                }
+                if !model.can_send(*sender, &token_id) {
+                    return Expected::Unauthorized;
+                }
+                model.tokens.insert(token_id, (*recipient, BTreeSet::new()));
+            }
+            Op::Approve {
+                sender,
+                spender,
+                token,
+            }
+            | Op::Revoke {
+                sender,
+                spender,
+                token,
+            } => {
+                let token_id = token_id(*token);
+                let owner = match model.tokens.get(&token_id) {
+                    Some((owner, _)) => *owner,
+                    None => return Expected::Failed,
+                };
+                if !model.can_approve(*sender, owner) {
+                    return Expected::Unauthorized;
+                }
+                let approvals = &mut model.tokens.get_mut(&token_id).unwrap().1;
+                if matches!(op, Op::Approve { .. }) {
+                    approvals.insert(*spender);
+                } else {
+                    approvals.remove(spender);
+                }
+            }
+            Op::ApproveAll { sender, operator } => {
+                model.operators.insert((*sender, *operator));
+            }
+            Op::RevokeAll { sender, operator } => {
+                model.operators.remove(&(*sender, *operator));
+            }
+            Op::Burn { sender, token } => {
+                let token_id = token_id(*token);
+                if !model.tokens.contains_key(&token_id) {
+                    return Expected::Failed;
+                }
+                if !model.can_send(*sender, &token_id) {
+                    return Expected::Unauthorized;
+                }
+                model.tokens.remove(&token_id);
+                model.burned.insert(token_id);
+            }
+        }
+        Expected::Ok
+    }
+
+    fn msg(op: &Op) -> (usize, ExecuteMsg<Option<Empty>, Empty>) {
+        match op.clone() {
+            Op::Mint {
+                sender,
+                owner,
+                token,
+            } => (
+                sender,
+                ExecuteMsg::Mint(MintMsg {
+                    token_id: token_id(token),
+                    owner: ACCOUNTS[owner].to_string(),
+                    token_uri: None,
+                    extension: None,
+                }),
+            ),
+            Op::Transfer {
+                sender,
+                recipient,
+                token,
+            } => (
+                sender,
+                ExecuteMsg::TransferNft {
+                    recipient: ACCOUNTS[recipient].to_string(),
+                    token_id: token_id(token),
+                },
+            ),
+            Op::Approve {
+                sender,
+                spender,
+                token,
+            } => (
+                sender,
+                ExecuteMsg::Approve {
+                    spender: ACCOUNTS[spender].to_string(),
+                    token_id: token_id(token),
+                    expires: None,
+                },
+            ),
+            Op::Revoke {
+                sender,
+                spender,
+                token,
+            } => (
+                sender,
+                ExecuteMsg::Revoke {
+                    spender: ACCOUNTS[spender].to_string(),
+                    token_id: token_id(token),
+                },
+            ),
+            Op::ApproveAll { sender, operator } => (
+                sender,
+                ExecuteMsg::ApproveAll {
+                    operator: ACCOUNTS[operator].to_string(),
+                    expires: None,
+                },
+            ),
+            Op::RevokeAll { sender, operator } => (
+                sender,
+                ExecuteMsg::RevokeAll {
+                    operator: ACCOUNTS[operator].to_string(),
+                },
+            ),
+            Op::Burn { sender, token } => (
+                sender,
+                ExecuteMsg::Burn {
+                    token_id: token_id(token),
+                },
+            ),
+        }
+    }
+
+    fn check_invariants(deps: Deps, model: &Model) {
+        let stored: Vec<(String, TokenInfo)> = get_tokens()
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // TOKENS_COUNT follows the stored tokens
+        assert_eq!(token_count(deps.storage).unwrap(), stored.len() as u64);
+
+        // the stats follow the stored tokens and their owners
+        let stats = get_stats(deps.storage).unwrap();
+        assert_eq!(stats.minted - stats.burned, stored.len() as u64);
+        let holders: BTreeSet<_> = stored.iter().map(|(_, token)| &token.owner).collect();
+        assert_eq!(stats.unique_holders, holders.len() as u64);
+
+        // the owner index matches TokenInfo.owner
+        #[cfg(not(feature = "non-enumerable"))]
+        for owner in ACCOUNTS {
+            let indexed: Vec<String> = get_tokens()
+                .idx
+                .owner
+                .prefix(cosmwasm_std::Addr::unchecked(owner))
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let expected: Vec<String> = stored
+                .iter()
+                .filter(|(_, token)| token.owner == owner)
+                .map(|(token_id, _)| token_id.clone())
+                .collect();
+            assert_eq!(indexed, expected);
+        }
+
+        // storage agrees with the model and the checks reject everyone the model rejects
+        assert_eq!(
+            stored.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            model.tokens.keys().collect::<Vec<_>>()
+        );
+        for (token_id, token) in &stored {
+            let (owner, approvals) = &model.tokens[token_id];
+            assert_eq!(token.owner, ACCOUNTS[*owner]);
+            let spenders: BTreeSet<&str> =
+                token.approvals.iter().map(|a| a.spender.as_str()).collect();
+            assert_eq!(
+                spenders,
+                approvals.iter().map(|spender| ACCOUNTS[*spender]).collect()
+            );
+
+            for (sender, account) in ACCOUNTS.iter().enumerate() {
+                let info = mock_info(account, &[]);
+                let can_approve =
+                    check_can_approve::<CustomError>(deps, &mock_env(), &info, token_id, token);
+                let can_send =
+                    check_can_send::<CustomError>(deps, &mock_env(), &info, token_id, token);
+
+                if model.can_approve(sender, *owner) {
+                    assert_eq!(can_approve, Ok(()));
+                } else {
+                    assert_eq!(can_approve, Err(ContractError::Unauthorized {}));
+                }
+                if model.can_send(sender, token_id) {
+                    assert_eq!(can_send, Ok(()));
+                } else {
+                    assert_eq!(can_send, Err(ContractError::Unauthorized {}));
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn token_state_invariants(ops in prop::collection::vec(op(), 1..64)) {
+            let mut deps = mock_dependencies();
+            instantiate::<Option<Empty>, Empty, CustomError>(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(MINTER, &[]),
+                InstantiateMsg {
+                    minter: MINTER.to_string(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let mut model = Model::default();
+            for op in &ops {
+                let expected = apply(&mut model, op);
+                let (sender, msg) = msg(op);
+                let res = execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info(ACCOUNTS[sender], &[]),
+                    msg,
+                    &TestExtension,
+                );
+
+                match expected {
+                    Expected::Ok => prop_assert!(res.is_ok(), "{:?} failed: {:?}", op, res),
+                    Expected::Unauthorized => {
+                        prop_assert_eq!(res.unwrap_err(), ContractError::Unauthorized {})
+                    }
+                    Expected::Failed => prop_assert!(res.is_err(), "{:?} succeeded", op),
+                }
+
+                // transfers always leave the token without approvals
+                if let (Op::Transfer { token, .. }, Expected::Ok) = (op, &expected) {
+                    let transferred = get_tokens().load(&deps.storage, &token_id(*token)).unwrap();
+                    prop_assert!(transferred.approvals.is_empty());
+                }
+
+                check_invariants(deps.as_ref(), &model);
             }
         }
-        None => Err(ContractError::Unauthorized {}),
     }
 }
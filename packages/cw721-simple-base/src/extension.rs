@@ -0,0 +1,114 @@
+use crate::error::ContractError;
+use crate::msg::MintMsg;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cw721::CustomMsg;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Debug;
+
+pub const UNSUPPORTED_EXTENSION_QUERY: &str = "Unsupported extension query";
+
+/// Implemented by contracts extending cw721-simple-base.
+/// contract::execute and contract::query call these hooks, so an extension can handle its own
+/// messages and veto (before_*) or add to (after_*) the core operations.
+/// Every hook has a default implementation, override only what you need.
+pub trait Cw721Extension {
+    /// Metadata extension stored with every token
+    type Extension: Serialize + DeserializeOwned + Clone;
+    /// Custom message carried by ExecuteMsg::Extension
+    type ExecuteMsg: Serialize + DeserializeOwned + Clone;
+    /// Custom message carried by QueryMsg::Extension
+    type QueryMsg: Serialize + DeserializeOwned + Clone;
+    /// Custom message of the returned Response
+    type CustomMsg: CustomMsg;
+    /// Custom error wrapped in ContractError::CustomError
+    type Error: Debug + PartialEq + Error;
+
+    /// handles ExecuteMsg::Extension, rejected as unsupported unless overridden
+    fn execute_extension(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Self::ExecuteMsg,
+    ) -> Result<Response<Self::CustomMsg>, ContractError<Self::Error>> {
+        Err(ContractError::UnsupportedExtensionMsg {})
+    }
+
+    /// handles QueryMsg::Extension, rejected as unsupported unless overridden
+    fn query_extension(&self, _deps: Deps, _env: Env, _msg: Self::QueryMsg) -> StdResult<Binary> {
+        Err(StdError::generic_err(UNSUPPORTED_EXTENSION_QUERY))
+    }
+
+    /// called before the token is minted, returning an error aborts the mint
+    fn before_mint(
+        &self,
+        _deps: Deps,
+        _env: &Env,
+        _info: &MessageInfo,
+        _msg: &MintMsg<Self::Extension>,
+    ) -> Result<(), ContractError<Self::Error>> {
+        Ok(())
+    }
+
+    /// called with the response of a successful mint
+    fn after_mint(
+        &self,
+        _deps: DepsMut,
+        _env: &Env,
+        _info: &MessageInfo,
+        _token_id: &str,
+        res: Response<Self::CustomMsg>,
+    ) -> Result<Response<Self::CustomMsg>, ContractError<Self::Error>> {
+        Ok(res)
+    }
+
+    /// called before transfer_nft and send_nft, returning an error aborts the transfer
+    fn before_transfer(
+        &self,
+        _deps: Deps,
+        _env: &Env,
+        _info: &MessageInfo,
+        _token_id: &str,
+        _recipient: &str,
+    ) -> Result<(), ContractError<Self::Error>> {
+        Ok(())
+    }
+
+    /// called with the response of a successful transfer_nft or send_nft
+    fn after_transfer(
+        &self,
+        _deps: DepsMut,
+        _env: &Env,
+        _info: &MessageInfo,
+        _token_id: &str,
+        _recipient: &str,
+        res: Response<Self::CustomMsg>,
+    ) -> Result<Response<Self::CustomMsg>, ContractError<Self::Error>> {
+        Ok(res)
+    }
+
+    /// called before the token is burned, returning an error aborts the burn
+    fn before_burn(
+        &self,
+        _deps: Deps,
+        _env: &Env,
+        _info: &MessageInfo,
+        _token_id: &str,
+    ) -> Result<(), ContractError<Self::Error>> {
+        Ok(())
+    }
+
+    /// called with the response of a successful burn
+    fn after_burn(
+        &self,
+        _deps: DepsMut,
+        _env: &Env,
+        _info: &MessageInfo,
+        _token_id: &str,
+        res: Response<Self::CustomMsg>,
+    ) -> Result<Response<Self::CustomMsg>, ContractError<Self::Error>> {
+        Ok(res)
+    }
+}
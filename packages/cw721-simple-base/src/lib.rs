@@ -1,5 +1,10 @@
+pub mod client;
 pub mod contract;
 pub mod error;
 pub mod execute;
+pub mod extension;
+pub mod msg;
 pub mod query;
 pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
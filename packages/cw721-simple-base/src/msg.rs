@@ -0,0 +1,674 @@
+use crate::state::{AddressListMode, GrantScope, HistoryAction, TokenIdPolicy};
+use cosmwasm_std::{Binary, Coin, Timestamp, Uint128};
+pub use cw20::{Cw20Coin, Cw20ReceiveMsg};
+use cw721::Expiration;
+pub use cw721_base::{MintMsg, MinterResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// cw721-base InstantiateMsg with the settings of cw721-simple-base,
+/// the JSON of a cw721-base InstantiateMsg is accepted as is
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg<T> {
+    /// Name of the NFT contract
+    pub name: String,
+    /// Symbol of the NFT contract
+    pub symbol: String,
+    /// The minter is the only one who can create new NFTs.
+    /// This is designed for a base NFT that is controlled by an external program
+    /// or contract. You will likely replace this with custom logic in custom NFTs
+    pub minter: String,
+    /// Can update the collection info, defaults to the minter
+    pub admin: Option<String>,
+    /// Collection level metadata for marketplaces
+    pub collection_info: Option<CollectionInfoMsg>,
+    /// Rules of the minted token ids, TokenIdPolicy::default() when unset
+    pub token_id_policy: Option<TokenIdPolicy>,
+    /// Tokens minted at instantiate, such as founder editions, with the checks of Mint
+    #[serde(default)]
+    pub initial_mints: Vec<MintMsg<T>>,
+    /// Burned token ids can be minted again, they are rejected by default
+    #[serde(default)]
+    pub allow_remint: bool,
+    /// History entries kept per token, DEFAULT_HISTORY_CAP when unset and 0 disables the history
+    pub history_cap: Option<u32>,
+    /// Flat fee of every transfer and send, transfers are free when unset
+    pub transfer_fee: Option<TransferFeeMsg>,
+}
+
+impl<T> From<cw721_base::InstantiateMsg> for InstantiateMsg<T> {
+    fn from(msg: cw721_base::InstantiateMsg) -> Self {
+        InstantiateMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            minter: msg.minter,
+            admin: None,
+            collection_info: None,
+            token_id_policy: None,
+            initial_mints: vec![],
+            allow_remint: false,
+            history_cap: None,
+            transfer_fee: None,
+        }
+    }
+}
+
+/// Upgrades the storage of a deployed collection, see contract::migrate.
+/// Migrates the first `limit` tokens, `migrate_tokens` goes through the next ones
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    pub limit: Option<u32>,
+}
+
+/// Collection level metadata beyond name and symbol, every URL is validated
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionInfoMsg {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub banner_url: Option<String>,
+    pub external_link: Option<String>,
+    pub creator: Option<String>,
+    #[serde(default)]
+    pub social_links: Vec<SocialLink>,
+}
+
+/// Message of the cw20 transfer paying for a mint, sent as the msg of Cw20ReceiveMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg<T> {
+    /// Mint the token to owner, the sender of the cw20 transfer when unset
+    Mint {
+        token_id: String,
+        owner: Option<String>,
+        token_uri: Option<String>,
+        extension: T,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeeMsg {
+    pub address: String,
+    /// Share of the revenue in basis points, 10000 is all of it
+    pub shares_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferFeeMsg {
+    /// Paid in info.funds by the sender of the transfer
+    pub amount: Coin,
+    /// Receives the fees
+    pub treasury: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SocialLink {
+    /// Name of the platform, e.g. twitter or discord
+    pub name: String,
+    pub url: String,
+}
+
+/// cw721-base ExecuteMsg and the messages of cw721-simple-base
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg<T, E> {
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft { recipient: String, token_id: String },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke { spender: String, token_id: String },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll { operator: String },
+    /// Allows operator to transfer / send and approve the tokens of the owner's account in the scope.
+    /// Replaces the previous scoped grant of the operator
+    ApproveScoped {
+        operator: String,
+        scope: GrantScope,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveScoped permission
+    RevokeScoped { operator: String },
+
+    /// Mint a new NFT, can only be called by the contract minter
+    Mint(MintMsg<T>),
+
+    /// Burn an NFT the sender has access to
+    Burn { token_id: String },
+
+    /// Receive is called by an accepted cw20 contract, the transfer pays for the
+    /// Cw20HookMsg embedded in msg
+    Receive(Cw20ReceiveMsg),
+    /// Mint a new NFT paid with the native price in the funds, open to anyone
+    /// while the price is set. Mints to owner, the sender when unset
+    PaidMint {
+        token_id: String,
+        owner: Option<String>,
+        token_uri: Option<String>,
+        extension: T,
+    },
+    /// Send the mint revenue credited to the sender
+    Withdraw {},
+
+    /// Replace the collection info, can only be called by the admin
+    UpdateCollectionInfo { collection_info: CollectionInfoMsg },
+    /// Set or remove the price of PaidMint, can only be called by the admin
+    UpdateNativePrice { price: Option<Coin> },
+    /// Replace the payees splitting the mint revenue, can only be called by the admin.
+    /// Their shares must add up to 10000 basis points, an empty list credits the admin
+    UpdatePayees { payees: Vec<PayeeMsg> },
+    /// Set or remove the mint price in the cw20 token, can only be called by the admin
+    UpdateCw20Price {
+        token: String,
+        price: Option<Uint128>,
+    },
+    /// Set or remove the fee of transfers, can only be called by the admin
+    UpdateTransferFee {
+        transfer_fee: Option<TransferFeeMsg>,
+    },
+
+    /// Allows manager to lock and unlock the tokens of the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveLockManager {
+        manager: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveLockManager permission, its locks stay
+    /// until the owner lifts them
+    RevokeLockManager { manager: String },
+    /// Lock the token against transfer, send and burn without moving it,
+    /// by the owner or a lock manager. If expiration is set, the lock is lifted then
+    LockToken {
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Lift the lock of the token, only the account that locked it can before it expires.
+    /// The owner can also lift the locks of managers it revoked
+    UnlockToken { token_id: String },
+
+    /// Add and remove addresses of the address list, and switch its mode when set.
+    /// Can only be called by the admin
+    UpdateAddressList {
+        mode: Option<AddressListMode>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Freeze the token against transfer, send, approve and burn, can only be called by the admin
+    FreezeToken { token_id: String, reason: String },
+    /// Unfreeze the token, can only be called by the admin
+    UnfreezeToken { token_id: String },
+
+    /// Offer the token to the recipient, it moves once the recipient accepts it.
+    /// Replaces the pending transfer of the token
+    OfferTransfer {
+        recipient: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Accept the pending transfer of the token, can only be called by its recipient
+    AcceptTransfer { token_id: String },
+    /// Drop the pending transfer of the token, by the sender of the offer or the owner
+    CancelTransfer { token_id: String },
+
+    /// Migrate the next `limit` tokens of the migration started by the migrate entry point,
+    /// can only be called by the admin
+    MigrateTokens { limit: Option<u32> },
+
+    /// Extension msg
+    Extension { msg: E },
+}
+
+impl<T, E> From<cw721_base::ExecuteMsg<T, E>> for ExecuteMsg<T, E> {
+    fn from(msg: cw721_base::ExecuteMsg<T, E>) -> Self {
+        match msg {
+            cw721_base::ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            } => ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            },
+            cw721_base::ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            } => ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            },
+            cw721_base::ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            } => ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            },
+            cw721_base::ExecuteMsg::Revoke { spender, token_id } => {
+                ExecuteMsg::Revoke { spender, token_id }
+            }
+            cw721_base::ExecuteMsg::ApproveAll { operator, expires } => {
+                ExecuteMsg::ApproveAll { operator, expires }
+            }
+            cw721_base::ExecuteMsg::RevokeAll { operator } => ExecuteMsg::RevokeAll { operator },
+            cw721_base::ExecuteMsg::Mint(msg) => ExecuteMsg::Mint(msg),
+            cw721_base::ExecuteMsg::Burn { token_id } => ExecuteMsg::Burn { token_id },
+            cw721_base::ExecuteMsg::Extension { msg } => ExecuteMsg::Extension { msg },
+        }
+    }
+}
+
+impl<T, E> ExecuteMsg<T, E> {
+    /// The token_id field of the message, if it has one
+    pub fn token_id_mut(&mut self) -> Option<&mut String> {
+        match self {
+            ExecuteMsg::TransferNft { token_id, .. }
+            | ExecuteMsg::SendNft { token_id, .. }
+            | ExecuteMsg::Approve { token_id, .. }
+            | ExecuteMsg::Revoke { token_id, .. }
+            | ExecuteMsg::Burn { token_id, .. }
+            | ExecuteMsg::PaidMint { token_id, .. }
+            | ExecuteMsg::LockToken { token_id, .. }
+            | ExecuteMsg::UnlockToken { token_id, .. }
+            | ExecuteMsg::FreezeToken { token_id, .. }
+            | ExecuteMsg::UnfreezeToken { token_id, .. }
+            | ExecuteMsg::OfferTransfer { token_id, .. }
+            | ExecuteMsg::AcceptTransfer { token_id, .. }
+            | ExecuteMsg::CancelTransfer { token_id, .. } => Some(token_id),
+            _ => None,
+        }
+    }
+}
+
+/// cw721-base QueryMsg and the queries of cw721-simple-base
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg<Q> {
+    /// Return the owner of the given token, error if token does not exist
+    /// Return type: OwnerOfResponse
+    OwnerOf {
+        token_id: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// Return operator that can access all of the owner's tokens.
+    /// Return type: `ApprovalResponse`
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    /// Return approvals that a token has
+    /// Return type: `ApprovalsResponse`
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// List all operators that can access all of the owner's tokens
+    /// Return type: `OperatorsResponse`
+    AllOperators {
+        owner: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Total number of tokens issued
+    NumTokens {},
+    /// With MetaData Extension.
+    /// Returns top-level metadata about the contract: `ContractInfoResponse`
+    ContractInfo {},
+    /// With MetaData Extension.
+    /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
+    /// but directly from the contract: `NftInfoResponse`
+    NftInfo { token_id: String },
+    /// With MetaData Extension.
+    /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
+    /// for clients: `AllNftInfo`
+    AllNftInfo {
+        token_id: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// With Enumerable extension.
+    /// Returns all tokens owned by the given address, [] if unset.
+    /// Return type: TokensResponse.
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// With Enumerable extension.
+    /// Requires pagination. Lists all token_ids controlled by the contract.
+    /// Return type: TokensResponse.
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return the minter
+    /// Return type: `MinterResponse`
+    Minter {},
+
+    /// ContractInfo with the extended collection info
+    /// Return type: `CollectionInfoResponse`
+    CollectionInfo {},
+    /// Burned token ids, same pagination as AllTokens
+    /// Return type: TokensResponse.
+    BurnedTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Price of PaidMint
+    /// Return type: `NativePriceResponse`
+    NativePrice {},
+    /// Payees splitting the mint revenue
+    /// Return type: `PayeesResponse`
+    Payees {},
+    /// Mint revenue the payee can withdraw
+    /// Return type: `ClaimableResponse`
+    Claimable { payee: String },
+    /// Mint price of each accepted cw20 token
+    /// Return type: `Cw20PricesResponse`
+    Cw20Prices {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// cw20 amounts received by paid mints, per cw20 token
+    /// Return type: `Cw20ProceedsResponse`
+    Cw20Proceeds {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Fee of transfers, sends and accepted transfers
+    /// Return type: `TransferFeeResponse`
+    TransferFee {},
+    /// Lifetime totals of the collection, NumTokens is the live supply
+    /// Return type: `StatsResponse`
+    Stats {},
+    /// Locked tokens of the owner
+    /// Return type: `LockedTokensResponse`
+    LockedTokens {
+        owner: String,
+        /// unset or false will filter out expired locks, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Mode and addresses of the address list
+    /// Return type: `AddressListResponse`
+    AddressList {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Frozen tokens with the reason of the freeze
+    /// Return type: `FrozenTokensResponse`
+    FrozenTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Scoped grant of the owner to the operator, error if there is none
+    /// Return type: `ScopedGrantResponse`
+    ScopedGrant {
+        owner: String,
+        operator: String,
+        include_expired: Option<bool>,
+    },
+    /// List the scoped grants of the owner
+    /// Return type: `ScopedGrantsResponse`
+    ScopedGrants {
+        owner: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Pending transfer of a token, error if there is none
+    /// Return type: `PendingTransferResponse`
+    PendingTransfer { token_id: String },
+    /// Pending transfers offered to the recipient
+    /// Return type: `PendingTransfersResponse`
+    PendingTransfers {
+        recipient: String,
+        /// unset or false will filter out expired offers, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Ownership history of a token, oldest first, burned tokens keep their history
+    /// Return type: `TokenHistoryResponse`
+    TokenHistory {
+        token_id: String,
+        /// seq of the last entry of the previous page
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Extension query
+    Extension { msg: Q },
+}
+
+impl<Q> From<cw721_base::QueryMsg<Q>> for QueryMsg<Q> {
+    fn from(msg: cw721_base::QueryMsg<Q>) -> Self {
+        match msg {
+            cw721_base::QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            } => QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            },
+            cw721_base::QueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+            } => QueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+            },
+            cw721_base::QueryMsg::Approvals {
+                token_id,
+                include_expired,
+            } => QueryMsg::Approvals {
+                token_id,
+                include_expired,
+            },
+            cw721_base::QueryMsg::AllOperators {
+                owner,
+                include_expired,
+                start_after,
+                limit,
+            } => QueryMsg::AllOperators {
+                owner,
+                include_expired,
+                start_after,
+                limit,
+            },
+            cw721_base::QueryMsg::NumTokens {} => QueryMsg::NumTokens {},
+            cw721_base::QueryMsg::ContractInfo {} => QueryMsg::ContractInfo {},
+            cw721_base::QueryMsg::NftInfo { token_id } => QueryMsg::NftInfo { token_id },
+            cw721_base::QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+            } => QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+            },
+            cw721_base::QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            } => QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            },
+            cw721_base::QueryMsg::AllTokens { start_after, limit } => {
+                QueryMsg::AllTokens { start_after, limit }
+            }
+            cw721_base::QueryMsg::Minter {} => QueryMsg::Minter {},
+            cw721_base::QueryMsg::Extension { msg } => QueryMsg::Extension { msg },
+        }
+    }
+}
+
+impl<Q> QueryMsg<Q> {
+    /// The token_id field of the message, if it has one
+    pub fn token_id_mut(&mut self) -> Option<&mut String> {
+        match self {
+            QueryMsg::OwnerOf { token_id, .. }
+            | QueryMsg::Approval { token_id, .. }
+            | QueryMsg::Approvals { token_id, .. }
+            | QueryMsg::NftInfo { token_id, .. }
+            | QueryMsg::AllNftInfo { token_id, .. }
+            | QueryMsg::PendingTransfer { token_id, .. }
+            | QueryMsg::TokenHistory { token_id, .. } => Some(token_id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub admin: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub banner_url: Option<String>,
+    pub external_link: Option<String>,
+    pub creator: Option<String>,
+    pub social_links: Vec<SocialLink>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub minted: u64,
+    pub burned: u64,
+    pub transferred: u64,
+    pub unique_holders: u64,
+    /// Block time of the last mint, unset before the first one
+    pub last_mint: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryEntryResponse {
+    /// Position in the history of the token, pruned entries leave a gap at the start
+    pub seq: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Unset for mints
+    pub from: Option<String>,
+    /// Unset for burns
+    pub to: Option<String>,
+    pub action: HistoryAction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenHistoryResponse {
+    pub entries: Vec<HistoryEntryResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransferResponse {
+    pub token_id: String,
+    pub sender: String,
+    pub recipient: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransfersResponse {
+    pub transfers: Vec<PendingTransferResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScopedGrantResponse {
+    pub operator: String,
+    pub scope: GrantScope,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScopedGrantsResponse {
+    pub grants: Vec<ScopedGrantResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddressListResponse {
+    pub mode: AddressListMode,
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenTokenResponse {
+    pub token_id: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenTokensResponse {
+    pub tokens: Vec<FrozenTokenResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockResponse {
+    pub token_id: String,
+    pub locked_by: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockedTokensResponse {
+    pub tokens: Vec<LockResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferFeeResponse {
+    pub transfer_fee: Option<TransferFeeMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20PricesResponse {
+    pub prices: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20ProceedsResponse {
+    pub proceeds: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NativePriceResponse {
+    pub price: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeesResponse {
+    pub payees: Vec<PayeeMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    pub native: Vec<Coin>,
+    pub cw20: Vec<Cw20Coin>,
+}
@@ -1,13 +1,27 @@
+use crate::error::{ContractError, CustomError};
+use crate::msg::{
+    AddressListResponse, ClaimableResponse, CollectionInfoResponse, Cw20Coin, Cw20PricesResponse,
+    Cw20ProceedsResponse, FrozenTokenResponse, FrozenTokensResponse, HistoryEntryResponse,
+    LockResponse, LockedTokensResponse, MinterResponse, NativePriceResponse, PayeeMsg,
+    PayeesResponse, PendingTransferResponse, PendingTransfersResponse, ScopedGrantResponse,
+    ScopedGrantsResponse, StatsResponse, TokenHistoryResponse, TransferFeeMsg, TransferFeeResponse,
+};
 use crate::state::{
-    get_minter, get_tokens, token_count, Approval, TokenInfo, CONTRACT_INFO, OPERATORS,
+    get_address_list_mode, get_admin, get_locks, get_minter, get_pending_transfers, get_stats,
+    get_tokens, load_pending_transfer, load_token, load_token_metadata, stats_seeding, token_count,
+    Approval, PendingTransfer, ScopedGrant, TokenInfo, ADDRESS_LIST, BURNED_TOKENS,
+    COLLECTION_INFO, CONTRACT_INFO, CW20_CLAIMABLE, CW20_PRICES, CW20_PROCEEDS, FROZEN_TOKENS,
+    NATIVE_CLAIMABLE, NATIVE_PRICE, OPERATORS, PAYEES, SCOPED_GRANTS, TOKEN_HISTORY, TRANSFER_FEE,
+};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, Coin, Deps, Env, Order, StdResult, Uint128,
 };
-use cosmwasm_std::{to_binary, Addr, Binary, BlockInfo, Deps, Env, Order, StdError, StdResult};
 use cw721::{
     AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Expiration, NftInfoResponse,
     NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
 };
-use cw721_base::MinterResponse;
 use cw_storage_plus::Bound;
+use cw_storage_plus::Map;
 use cw_utils::maybe_addr;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -25,47 +39,165 @@ pub fn contract_info(deps: Deps) -> StdResult<Binary> {
     to_binary(&CONTRACT_INFO.load(deps.storage)?)
 }
 
+/// contract info and the extended collection info, empty until it is set
+pub fn collection_info(deps: Deps) -> StdResult<Binary> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    let collection_info = COLLECTION_INFO.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&CollectionInfoResponse {
+        name: contract_info.name,
+        symbol: contract_info.symbol,
+        admin: get_admin(deps.storage)?.to_string(),
+        description: collection_info.description,
+        image: collection_info.image,
+        banner_url: collection_info.banner_url,
+        external_link: collection_info.external_link,
+        creator: collection_info.creator.map(String::from),
+        social_links: collection_info.social_links,
+    })
+}
+
 pub fn num_tokens(deps: Deps) -> StdResult<Binary> {
     to_binary(&NumTokensResponse {
         count: token_count(deps.storage)?,
     })
 }
 
+pub fn native_price(deps: Deps) -> StdResult<Binary> {
+    to_binary(&NativePriceResponse {
+        price: NATIVE_PRICE.may_load(deps.storage)?,
+    })
+}
+
+pub fn payees(deps: Deps) -> StdResult<Binary> {
+    let payees = PAYEES.may_load(deps.storage)?.unwrap_or_default();
+    to_binary(&PayeesResponse {
+        payees: payees
+            .into_iter()
+            .map(|payee| PayeeMsg {
+                address: payee.address.into_string(),
+                shares_bps: payee.shares_bps,
+            })
+            .collect(),
+    })
+}
+
+pub fn claimable(deps: Deps, payee: String) -> StdResult<Binary> {
+    let payee_addr = deps.api.addr_validate(&payee)?;
+    let native: StdResult<Vec<Coin>> = NATIVE_CLAIMABLE
+        .prefix(&payee_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect();
+    let cw20: StdResult<Vec<Cw20Coin>> = CW20_CLAIMABLE
+        .prefix(&payee_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(address, amount)| Cw20Coin {
+                address: address.into_string(),
+                amount,
+            })
+        })
+        .collect();
+
+    to_binary(&ClaimableResponse {
+        native: native?,
+        cw20: cw20?,
+    })
+}
+
+pub fn cw20_prices(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let prices = cw20_amounts(deps, CW20_PRICES, start_after, limit)?;
+    to_binary(&Cw20PricesResponse { prices })
+}
+
+pub fn cw20_proceeds(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let proceeds = cw20_amounts(deps, CW20_PROCEEDS, start_after, limit)?;
+    to_binary(&Cw20ProceedsResponse { proceeds })
+}
+
+fn cw20_amounts(
+    deps: Deps,
+    map: Map<&Addr, Uint128>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Cw20Coin>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    map.range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(address, amount)| Cw20Coin {
+                address: address.into_string(),
+                amount,
+            })
+        })
+        .collect()
+}
+
+pub fn transfer_fee(deps: Deps) -> StdResult<Binary> {
+    let transfer_fee = TRANSFER_FEE.may_load(deps.storage)?;
+    to_binary(&TransferFeeResponse {
+        transfer_fee: transfer_fee.map(|fee| TransferFeeMsg {
+            amount: fee.amount,
+            treasury: fee.treasury.into_string(),
+        }),
+    })
+}
+
+/// fails while the migration is seeding the stats, partial counts would look final
+pub fn stats(deps: Deps) -> StdResult<Binary> {
+    if stats_seeding(deps.storage)? {
+        return Err(ContractError::<CustomError>::StatsSeeding {}.into());
+    }
+    let stats = get_stats(deps.storage)?;
+    to_binary(&StatsResponse {
+        minted: stats.minted,
+        burned: stats.burned,
+        transferred: stats.transferred,
+        unique_holders: stats.unique_holders,
+        last_mint: stats.last_mint,
+    })
+}
+
 pub fn nft_info<T>(deps: Deps, token_id: String) -> StdResult<Binary>
 where
     T: Serialize + DeserializeOwned + Clone,
 {
-    let info: TokenInfo<T> = get_tokens().load(deps.storage, &token_id)?;
+    let metadata = load_token_metadata::<T, CustomError>(deps.storage, &token_id)?;
     to_binary(&NftInfoResponse {
-        token_uri: info.token_uri,
-        extension: info.extension,
+        token_uri: metadata.token_uri,
+        extension: metadata.extension,
     })
 }
 
-pub fn owner_of<T>(
+pub fn owner_of(
     deps: Deps,
     env: Env,
     token_id: String,
     include_expired: bool,
-) -> StdResult<Binary>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
-    let info: TokenInfo<T> = get_tokens().load(deps.storage, &token_id)?;
+) -> StdResult<Binary> {
+    let info = load_token::<CustomError>(deps.storage, &token_id)?;
     to_binary(&OwnerOfResponse {
         owner: info.owner.to_string(),
         approvals: humanize_approvals(&env.block, &info, include_expired),
     })
 }
 
-fn humanize_approvals<T>(
+fn humanize_approvals(
     block: &BlockInfo,
-    info: &TokenInfo<T>,
+    info: &TokenInfo,
     include_expired: bool,
-) -> Vec<cw721::Approval>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
+) -> Vec<cw721::Approval> {
     info.approvals
         .iter()
         .filter(|apr| include_expired || !apr.is_expired(block))
@@ -104,17 +236,133 @@ pub fn operators(
     to_binary(&OperatorsResponse { operators: res? })
 }
 
-pub fn approval<T>(
+pub fn locked_tokens(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let tokens: StdResult<Vec<LockResponse>> = get_locks()
+        .idx
+        .owner
+        .prefix(owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block))
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, lock)| LockResponse {
+                token_id,
+                locked_by: lock.locked_by.into_string(),
+                expires: lock.expires,
+            })
+        })
+        .collect();
+
+    to_binary(&LockedTokensResponse { tokens: tokens? })
+}
+
+pub fn address_list(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let addresses: StdResult<Vec<String>> = ADDRESS_LIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(String::from))
+        .collect();
+
+    to_binary(&AddressListResponse {
+        mode: get_address_list_mode(deps.storage)?,
+        addresses: addresses?,
+    })
+}
+
+pub fn frozen_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens: StdResult<Vec<FrozenTokenResponse>> = FROZEN_TOKENS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(token_id, reason)| FrozenTokenResponse { token_id, reason }))
+        .collect();
+
+    to_binary(&FrozenTokensResponse { tokens: tokens? })
+}
+
+pub fn scoped_grant(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    operator: String,
+    include_expired: bool,
+) -> StdResult<Binary> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let operator_addr = deps.api.addr_validate(&operator)?;
+
+    match SCOPED_GRANTS.may_load(deps.storage, (&owner_addr, &operator_addr))? {
+        Some(grant) if include_expired || !grant.is_expired(&env.block) => {
+            to_binary(&scoped_grant_response(operator_addr, grant))
+        }
+        _ => Err(ContractError::<CustomError>::ApprovalNotFound { spender: operator }.into()),
+    }
+}
+
+/// scoped grants of the owner by operator
+pub fn scoped_grants(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let grants: StdResult<Vec<_>> = SCOPED_GRANTS
+        .prefix(&owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block))
+        .take(limit)
+        .map(|item| item.map(|(operator, grant)| scoped_grant_response(operator, grant)))
+        .collect();
+    to_binary(&ScopedGrantsResponse { grants: grants? })
+}
+
+fn scoped_grant_response(operator: Addr, grant: ScopedGrant) -> ScopedGrantResponse {
+    ScopedGrantResponse {
+        operator: operator.into_string(),
+        scope: grant.scope,
+        expires: grant.expires,
+    }
+}
+
+pub fn approval(
     deps: Deps,
     env: Env,
     token_id: String,
     spender: String,
     include_expired: bool,
-) -> StdResult<Binary>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
-    let token: TokenInfo<T> = get_tokens().load(deps.storage, &token_id)?;
+) -> StdResult<Binary> {
+    let token = load_token::<CustomError>(deps.storage, &token_id)?;
 
     // token owner has absolute approval
     if token.owner == spender {
@@ -137,7 +385,7 @@ where
         .collect();
 
     if filtered.is_empty() {
-        return Err(StdError::not_found("Approval not found"));
+        return Err(ContractError::<CustomError>::ApprovalNotFound { spender }.into());
     }
     // we expect only one item
     let approval = filtered[0].clone();
@@ -146,16 +394,13 @@ where
 }
 
 /// approvals returns all approvals owner given access to
-pub fn approvals<T>(
+pub fn approvals(
     deps: Deps,
     env: Env,
     token_id: String,
     include_expired: bool,
-) -> StdResult<Binary>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
-    let token: TokenInfo<T> = get_tokens().load(deps.storage, &token_id)?;
+) -> StdResult<Binary> {
+    let token = load_token::<CustomError>(deps.storage, &token_id)?;
     let approvals: Vec<_> = token
         .approvals
         .into_iter()
@@ -169,20 +414,18 @@ where
     to_binary(&ApprovalsResponse { approvals })
 }
 
-pub fn tokens<T>(
+pub fn tokens(
     deps: Deps,
     owner: String,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<Binary>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
+) -> StdResult<Binary> {
+    check_enumerable()?;
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
     let owner_addr = deps.api.addr_validate(&owner)?;
-    let tokens: Vec<String> = get_tokens::<T>()
+    let tokens: Vec<String> = get_tokens()
         .idx
         .owner
         .prefix(owner_addr)
@@ -193,18 +436,16 @@ where
     to_binary(&TokensResponse { tokens })
 }
 
-pub fn all_tokens<T>(
+pub fn all_tokens(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<Binary>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
+) -> StdResult<Binary> {
+    check_enumerable()?;
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
-    let tokens: StdResult<Vec<String>> = get_tokens::<T>()
+    let tokens: StdResult<Vec<String>> = get_tokens()
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| item.map(|(k, _)| k))
@@ -213,6 +454,105 @@ where
     to_binary(&TokensResponse { tokens: tokens? })
 }
 
+/// burned token ids, listed even when token enumeration is disabled
+pub fn burned_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens: StdResult<Vec<String>> = BURNED_TOKENS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+
+    to_binary(&TokensResponse { tokens: tokens? })
+}
+
+pub fn token_history(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries: StdResult<Vec<HistoryEntryResponse>> = TOKEN_HISTORY
+        .prefix(&token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(seq, entry)| HistoryEntryResponse {
+                seq,
+                height: entry.height,
+                time: entry.time,
+                from: entry.from.map(String::from),
+                to: entry.to.map(String::from),
+                action: entry.action,
+            })
+        })
+        .collect();
+
+    to_binary(&TokenHistoryResponse { entries: entries? })
+}
+
+fn pending_transfer_response(
+    token_id: String,
+    pending: PendingTransfer,
+) -> PendingTransferResponse {
+    PendingTransferResponse {
+        token_id,
+        sender: pending.sender.into_string(),
+        recipient: pending.recipient.into_string(),
+        expires: pending.expires,
+    }
+}
+
+pub fn pending_transfer(deps: Deps, token_id: String) -> StdResult<Binary> {
+    let pending = load_pending_transfer::<CustomError>(deps.storage, &token_id)?;
+    to_binary(&pending_transfer_response(token_id, pending))
+}
+
+pub fn pending_transfers(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let transfers: StdResult<Vec<PendingTransferResponse>> = get_pending_transfers()
+        .idx
+        .recipient
+        .prefix(recipient_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| {
+            include_expired || r.is_err() || !r.as_ref().unwrap().1.expires.is_expired(&env.block)
+        })
+        .take(limit)
+        .map(|item| item.map(|(token_id, pending)| pending_transfer_response(token_id, pending)))
+        .collect();
+
+    to_binary(&PendingTransfersResponse {
+        transfers: transfers?,
+    })
+}
+
+/// Tokens and AllTokens are rejected when built with the non-enumerable feature
+fn check_enumerable() -> StdResult<()> {
+    if cfg!(feature = "non-enumerable") {
+        return Err(ContractError::<CustomError>::EnumerationDisabled {}.into());
+    }
+    Ok(())
+}
+
 pub fn all_nft_info<T>(
     deps: Deps,
     env: Env,
@@ -222,15 +562,16 @@ pub fn all_nft_info<T>(
 where
     T: Serialize + DeserializeOwned + Clone,
 {
-    let info: TokenInfo<T> = get_tokens().load(deps.storage, &token_id)?;
+    let info = load_token::<CustomError>(deps.storage, &token_id)?;
+    let metadata = load_token_metadata::<T, CustomError>(deps.storage, &token_id)?;
     to_binary(&AllNftInfoResponse {
         access: OwnerOfResponse {
             owner: info.owner.to_string(),
             approvals: humanize_approvals(&env.block, &info, include_expired),
         },
         info: NftInfoResponse {
-            token_uri: info.token_uri,
-            extension: info.extension,
+            token_uri: metadata.token_uri,
+            extension: metadata.extension,
         },
     })
 }
@@ -241,3 +582,306 @@ fn parse_approval(item: StdResult<(Addr, Expiration)>) -> StdResult<cw721::Appro
         expires,
     })
 }
+
+#[cfg(test)]
+mod query_tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::{ContractError, CustomError};
+    use crate::msg::{
+        ExecuteMsg, HistoryEntryResponse, InstantiateMsg, QueryMsg, StatsResponse,
+        TokenHistoryResponse,
+    };
+    use crate::state::HistoryAction;
+    use crate::testing::fixtures::{
+        approve, burn, init, mint, transfer_nft, Extension, TestExtension, ADDR1, ADDR2,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, to_binary, Deps, Empty, StdError};
+    use cw721::TokensResponse;
+
+    #[test]
+    fn test_approval_not_found() {
+        let mut deps = mock_dependencies();
+
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        approve(deps.as_mut(), ADDR1, ADDR2).unwrap();
+
+        let missing_approval_err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Approval {
+                token_id: "1".to_string(),
+                spender: ADDR1.to_string() + "_",
+                include_expired: None,
+            },
+            &TestExtension,
+        )
+        .unwrap_err();
+        assert_eq!(
+            missing_approval_err,
+            StdError::not_found("Approval not found")
+        );
+    }
+
+    fn query_burned_tokens(deps: Deps, start_after: Option<String>) -> Vec<String> {
+        let res: TokensResponse = from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::BurnedTokens {
+                    start_after,
+                    limit: Some(2),
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.tokens
+    }
+
+    #[test]
+    fn test_burned_tokens() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        for token_id in ["1", "2", "3", "4"] {
+            mint(deps.as_mut(), ADDR1, token_id).unwrap();
+        }
+        for token_id in ["3", "1", "2"] {
+            burn(deps.as_mut(), token_id).unwrap();
+        }
+
+        // burned ids can't be minted again, even for a new owner
+        let err = mint(deps.as_mut(), ADDR2, "1").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TokenBurned {
+                token_id: "1".to_string()
+            }
+        );
+        assert_eq!(err.code(), "token_burned");
+
+        assert_eq!(query_burned_tokens(deps.as_ref(), None), vec!["1", "2"]);
+        assert_eq!(
+            query_burned_tokens(deps.as_ref(), Some("2".to_string())),
+            vec!["3"]
+        );
+
+        // collections allowing re-mints clear the tombstone
+        let mut deps = mock_dependencies();
+        instantiate::<Extension, Empty, CustomError>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            InstantiateMsg {
+                minter: ADDR1.to_string(),
+                allow_remint: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        burn(deps.as_mut(), "1").unwrap();
+        assert_eq!(query_burned_tokens(deps.as_ref(), None), vec!["1"]);
+        mint(deps.as_mut(), ADDR2, "1").unwrap();
+        assert!(query_burned_tokens(deps.as_ref(), None).is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        let query_stats = |deps: Deps| -> StatsResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::Stats {}, &TestExtension).unwrap())
+                .unwrap()
+        };
+        assert_eq!(
+            query_stats(deps.as_ref()),
+            StatsResponse {
+                minted: 0,
+                burned: 0,
+                transferred: 0,
+                unique_holders: 0,
+                last_mint: None,
+            }
+        );
+
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        mint(deps.as_mut(), ADDR1, "2").unwrap();
+        transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        assert_eq!(
+            query_stats(deps.as_ref()),
+            StatsResponse {
+                minted: 2,
+                burned: 0,
+                transferred: 1,
+                unique_holders: 2,
+                last_mint: Some(mock_env().block.time),
+            }
+        );
+
+        // ADDR2 leaves the holders with its only token
+        transfer_nft(deps.as_mut(), ADDR2, ADDR1).unwrap();
+        burn(deps.as_mut(), "2").unwrap();
+        let stats = query_stats(deps.as_ref());
+        assert_eq!((stats.minted, stats.burned, stats.transferred), (2, 1, 2));
+        assert_eq!(stats.unique_holders, 1);
+
+        // failed mints are not counted
+        mint(deps.as_mut(), ADDR1, "1").unwrap_err();
+        assert_eq!(query_stats(deps.as_ref()).minted, 2);
+    }
+
+    #[test]
+    fn test_token_history() {
+        let init_with = |history_cap: Option<u32>| {
+            let mut deps = mock_dependencies();
+            instantiate::<Extension, Empty, CustomError>(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADDR1, &[]),
+                InstantiateMsg {
+                    minter: ADDR1.to_string(),
+                    history_cap,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            deps
+        };
+        let query_history = |deps: Deps, start_after: Option<u64>| -> TokenHistoryResponse {
+            let msg = QueryMsg::TokenHistory {
+                token_id: "1".to_string(),
+                start_after,
+                limit: Some(2),
+            };
+            from_binary(&query(deps, mock_env(), msg, &TestExtension).unwrap()).unwrap()
+        };
+
+        let mut deps = init_with(None);
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        let mut env = mock_env();
+        env.block.height += 1;
+        let send_msg = ExecuteMsg::SendNft {
+            contract: ADDR1.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary("hello").unwrap(),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            send_msg,
+            &TestExtension,
+        )
+        .unwrap();
+        burn(deps.as_mut(), "1").unwrap();
+
+        let block = mock_env().block;
+        assert_eq!(
+            query_history(deps.as_ref(), None).entries,
+            vec![
+                HistoryEntryResponse {
+                    seq: 0,
+                    height: block.height,
+                    time: block.time,
+                    from: None,
+                    to: Some(ADDR1.to_string()),
+                    action: HistoryAction::Mint,
+                },
+                HistoryEntryResponse {
+                    seq: 1,
+                    height: block.height,
+                    time: block.time,
+                    from: Some(ADDR1.to_string()),
+                    to: Some(ADDR2.to_string()),
+                    action: HistoryAction::Transfer,
+                },
+            ]
+        );
+        // burned tokens keep their history
+        assert_eq!(
+            query_history(deps.as_ref(), Some(1)).entries,
+            vec![
+                HistoryEntryResponse {
+                    seq: 2,
+                    height: env.block.height,
+                    time: env.block.time,
+                    from: Some(ADDR2.to_string()),
+                    to: Some(ADDR1.to_string()),
+                    action: HistoryAction::Send,
+                },
+                HistoryEntryResponse {
+                    seq: 3,
+                    height: block.height,
+                    time: block.time,
+                    from: Some(ADDR1.to_string()),
+                    to: None,
+                    action: HistoryAction::Burn,
+                },
+            ]
+        );
+
+        // the oldest entries are pruned past the cap
+        let mut deps = init_with(Some(2));
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        transfer_nft(deps.as_mut(), ADDR2, ADDR1).unwrap();
+        let seqs: Vec<u64> = query_history(deps.as_ref(), None)
+            .entries
+            .iter()
+            .map(|entry| entry.seq)
+            .collect();
+        assert_eq!(seqs, vec![1, 2]);
+
+        // a cap of 0 disables the history
+        let mut deps = init_with(Some(0));
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+        assert!(query_history(deps.as_ref(), None).entries.is_empty());
+    }
+
+    #[cfg(feature = "non-enumerable")]
+    #[test]
+    fn test_non_enumerable() {
+        let mut deps = mock_dependencies();
+        init(deps.as_mut());
+        mint(deps.as_mut(), ADDR1, "1").unwrap();
+
+        for msg in [
+            QueryMsg::Tokens {
+                owner: ADDR1.to_string(),
+                start_after: None,
+                limit: None,
+            },
+            QueryMsg::AllTokens {
+                start_after: None,
+                limit: None,
+            },
+        ] {
+            let err = query(deps.as_ref(), mock_env(), msg, &TestExtension).unwrap_err();
+            assert_eq!(
+                err,
+                StdError::generic_err("enumeration_disabled: Token enumeration is disabled")
+            );
+        }
+
+        // the rest of the collection works as usual
+        transfer_nft(deps.as_mut(), ADDR1, ADDR2).unwrap();
+        let owner_of: cw721::OwnerOfResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+                &TestExtension,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner_of.owner, ADDR2);
+    }
+}
@@ -1,10 +1,16 @@
 use crate::error::ContractError;
-use cosmwasm_std::{Addr, BlockInfo, Empty, Response, StdResult, Storage};
+use crate::msg::SocialLink;
+use cosmwasm_std::{
+    Addr, BlockInfo, Coin, Empty, Order, OverflowError, OverflowOperation, Response, StdResult,
+    Storage, Timestamp, Uint128,
+};
 use cw721::{ContractInfoResponse, Expiration};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::Debug;
 
 const CONTRACT_KEY: &str = "nft_info";
 const MINTER_KEY: &str = "minter";
@@ -12,19 +18,291 @@ const TOKEN_COUNT_KEY: &str = "num_tokens";
 const OPERATORS_KEY: &str = "operators";
 const TOKENS_KEY: &str = "tokens";
 const TOKENS_OWNER_KEY: &str = "tokens__owner";
+const TOKEN_METADATA_KEY: &str = "token_metadata";
+const ADMIN_KEY: &str = "admin";
+const COLLECTION_INFO_KEY: &str = "collection_info";
+const TOKEN_ID_POLICY_KEY: &str = "token_id_policy";
+const ALLOW_REMINT_KEY: &str = "allow_remint";
+const BURNED_TOKENS_KEY: &str = "burned_tokens";
+const STATS_KEY: &str = "stats";
+const HOLDER_BALANCES_KEY: &str = "holder_balances";
+const HISTORY_CAP_KEY: &str = "history_cap";
+const TOKEN_HISTORY_KEY: &str = "token_history";
+const PENDING_TRANSFERS_KEY: &str = "pending_transfers";
+const PENDING_TRANSFERS_RECIPIENT_KEY: &str = "pending_transfers__recipient";
+const SCOPED_GRANTS_KEY: &str = "scoped_grants";
+const ADDRESS_LIST_MODE_KEY: &str = "address_list_mode";
+const ADDRESS_LIST_KEY: &str = "address_list";
+const FROZEN_TOKENS_KEY: &str = "frozen_tokens";
+const LOCK_MANAGERS_KEY: &str = "lock_managers";
+const LOCKS_KEY: &str = "locks";
+const LOCKS_OWNER_KEY: &str = "locks__owner";
+const TRANSFER_FEE_KEY: &str = "transfer_fee";
+const CW20_PRICES_KEY: &str = "cw20_prices";
+const CW20_PROCEEDS_KEY: &str = "cw20_proceeds";
+const NATIVE_PRICE_KEY: &str = "native_price";
+const PAYEES_KEY: &str = "payees";
+const NATIVE_CLAIMABLE_KEY: &str = "native_claimable";
+const CW20_CLAIMABLE_KEY: &str = "cw20_claimable";
+const MIGRATION_KEY: &str = "migration";
+const ENUMERABLE_KEY: &str = "enumerable";
+
+pub const DEFAULT_HISTORY_CAP: u32 = 100;
+pub const DEFAULT_MIGRATION_LIMIT: u32 = 100;
 
 pub const CONTRACT_INFO: Item<ContractInfoResponse> = Item::new(CONTRACT_KEY);
 pub const MINTER: Item<Addr> = Item::new(MINTER_KEY);
 pub const TOKENS_COUNT: Item<u64> = Item::new(TOKEN_COUNT_KEY);
 pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new(OPERATORS_KEY);
+pub const ADMIN: Item<Addr> = Item::new(ADMIN_KEY);
+/// Extended collection info, CONTRACT_INFO keeps name and symbol
+pub const COLLECTION_INFO: Item<CollectionInfo> = Item::new(COLLECTION_INFO_KEY);
+pub const TOKEN_ID_POLICY: Item<TokenIdPolicy> = Item::new(TOKEN_ID_POLICY_KEY);
+pub const ALLOW_REMINT: Item<bool> = Item::new(ALLOW_REMINT_KEY);
+/// Tombstones of burned token ids, mint rejects them unless ALLOW_REMINT is set
+pub const BURNED_TOKENS: Map<&str, Empty> = Map::new(BURNED_TOKENS_KEY);
+pub const STATS: Item<Stats> = Item::new(STATS_KEY);
+/// Number of tokens of each holder, counts unique holders without the owner index
+pub const HOLDER_BALANCES: Map<&Addr, u64> = Map::new(HOLDER_BALANCES_KEY);
+pub const HISTORY_CAP: Item<u32> = Item::new(HISTORY_CAP_KEY);
+/// History of each token by sequence number, the oldest entries are pruned past HISTORY_CAP
+pub const TOKEN_HISTORY: Map<(&str, u64), HistoryEntry> = Map::new(TOKEN_HISTORY_KEY);
+/// Operators limited to a scope of tokens, by (owner, operator)
+pub const SCOPED_GRANTS: Map<(&Addr, &Addr), ScopedGrant> = Map::new(SCOPED_GRANTS_KEY);
+
+pub const MAX_SCOPED_TOKENS: u32 = 100;
+pub const ADDRESS_LIST_MODE: Item<AddressListMode> = Item::new(ADDRESS_LIST_MODE_KEY);
+/// Addresses blocked from or allowed to receive tokens, depending on ADDRESS_LIST_MODE
+pub const ADDRESS_LIST: Map<&Addr, Empty> = Map::new(ADDRESS_LIST_KEY);
+/// Tokens frozen by the admin with the reason of the freeze
+pub const FROZEN_TOKENS: Map<&str, String> = Map::new(FROZEN_TOKENS_KEY);
+/// Accounts an owner allowed to lock and unlock its tokens, by (owner, manager)
+pub const LOCK_MANAGERS: Map<(&Addr, &Addr), Expiration> = Map::new(LOCK_MANAGERS_KEY);
+/// Flat fee of every transfer and send, transfers are free when unset
+pub const TRANSFER_FEE: Item<TransferFee> = Item::new(TRANSFER_FEE_KEY);
+/// Mint price of each accepted cw20 contract
+pub const CW20_PRICES: Map<&Addr, Uint128> = Map::new(CW20_PRICES_KEY);
+/// cw20 amounts received by paid mints, by cw20 contract
+pub const CW20_PROCEEDS: Map<&Addr, Uint128> = Map::new(CW20_PROCEEDS_KEY);
+/// Price of PaidMint, paid mints are disabled when unset
+pub const NATIVE_PRICE: Item<Coin> = Item::new(NATIVE_PRICE_KEY);
+/// Payees splitting the mint revenue, the admin receives all of it when unset
+pub const PAYEES: Item<Vec<Payee>> = Item::new(PAYEES_KEY);
+/// Native revenue a payee can withdraw, by (payee, denom)
+pub const NATIVE_CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new(NATIVE_CLAIMABLE_KEY);
+/// cw20 revenue a payee can withdraw, by (payee, cw20 contract)
+pub const CW20_CLAIMABLE: Map<(&Addr, &Addr), Uint128> = Map::new(CW20_CLAIMABLE_KEY);
+/// Whether this build writes the owner index, see the non-enumerable feature
+pub const ENUMERABLE_BUILD: bool = !cfg!(feature = "non-enumerable");
+/// ENUMERABLE_BUILD of the instantiating build, collections without it were enumerable.
+/// A migration can't change it, the owner index would go stale
+pub const ENUMERABLE: Item<bool> = Item::new(ENUMERABLE_KEY);
+/// Progress of the paged migration of the token records, saved as done at instantiate
+pub const MIGRATION: Item<Migration> = Item::new(MIGRATION_KEY);
+
+pub const TOTAL_SHARES_BPS: u16 = 10_000;
+pub const MAX_PAYEES: u32 = 20;
+
+/// Collection level metadata for marketplaces, URLs are validated before saving
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionInfo {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub banner_url: Option<String>,
+    pub external_link: Option<String>,
+    pub creator: Option<Addr>,
+    pub social_links: Vec<SocialLink>,
+}
+
+/// Fee paid in a native denom on every transfer, forwarded to the treasury
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferFee {
+    pub amount: Coin,
+    pub treasury: Addr,
+}
+
+/// Payee of the mint revenue with its share in basis points
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payee {
+    pub address: Addr,
+    pub shares_bps: u16,
+}
+
+/// Cursor of the migration started by contract::migrate, executes are paused until it is done
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Migration {
+    /// Last token migrated, the next batch starts after it
+    pub last_token: Option<String>,
+    pub done: bool,
+    /// Set when STATS was not tracked yet, the batches seed it from the tokens they go through
+    #[serde(default)]
+    pub seed_stats: bool,
+}
+
+/// Lifetime totals of the collection, TOKENS_COUNT keeps the live supply
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Stats {
+    pub minted: u64,
+    pub burned: u64,
+    pub transferred: u64,
+    pub unique_holders: u64,
+    pub last_mint: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryAction {
+    Mint,
+    Transfer,
+    Send,
+    Burn,
+}
+
+/// One change of owner, from is unset for mints and to is unset for burns
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryEntry {
+    pub height: u64,
+    pub time: Timestamp,
+    pub from: Option<Addr>,
+    pub to: Option<Addr>,
+    pub action: HistoryAction,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        block: &BlockInfo,
+        from: Option<Addr>,
+        to: Option<Addr>,
+        action: HistoryAction,
+    ) -> Self {
+        HistoryEntry {
+            height: block.height,
+            time: block.time,
+            from,
+            to,
+            action,
+        }
+    }
+}
+
+/// How ADDRESS_LIST applies to recipients and spenders
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressListMode {
+    /// Listed addresses can't receive tokens
+    #[default]
+    Blocklist,
+    /// Only listed addresses can receive tokens
+    Allowlist,
+}
+
+/// Tokens of the owner an operator of a scoped grant can send and approve
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantScope {
+    /// The listed tokens, at most MAX_SCOPED_TOKENS
+    Tokens { token_ids: Vec<String> },
+    /// Tokens whose token_id starts with the prefix
+    Prefix { prefix: String },
+}
+
+impl GrantScope {
+    pub fn contains(&self, token_id: &str) -> bool {
+        match self {
+            GrantScope::Tokens { token_ids } => token_ids.iter().any(|id| id == token_id),
+            GrantScope::Prefix { prefix } => token_id.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScopedGrant {
+    pub scope: GrantScope,
+    pub expires: Expiration,
+}
+
+impl ScopedGrant {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+/// Rules a token_id must follow to be minted, set at instantiate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct TokenIdPolicy {
+    /// Maximum length in bytes
+    pub max_length: u32,
+    /// Allowed characters. When unset any Unicode character but whitespace and control
+    /// characters is accepted, as cw721-base does, so ids can look alike. The classes are
+    /// ASCII only, set them to rule out look-alike ids
+    pub allowed_chars: Option<Vec<CharClass>>,
+    /// Decimal numbers without leading zeros, allowed_chars is ignored
+    pub numeric_only: bool,
+    /// Lowercase the token_id of every message and query, so ids differing only by case
+    /// name the same token
+    pub case_folding: bool,
+}
+
+impl TokenIdPolicy {
+    /// the token_id as it is stored, lowercased with case folding
+    pub fn fold(&self, token_id: &str) -> String {
+        if self.case_folding {
+            token_id.to_lowercase()
+        } else {
+            token_id.to_string()
+        }
+    }
+}
+
+impl Default for TokenIdPolicy {
+    fn default() -> Self {
+        TokenIdPolicy {
+            max_length: 256,
+            allowed_chars: None,
+            numeric_only: false,
+            case_folding: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CharClass {
+    /// a-z
+    Lowercase,
+    /// A-Z
+    Uppercase,
+    /// 0-9
+    Digit,
+    /// - _ . :
+    Punctuation,
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            CharClass::Lowercase => c.is_ascii_lowercase(),
+            CharClass::Uppercase => c.is_ascii_uppercase(),
+            CharClass::Digit => c.is_ascii_digit(),
+            CharClass::Punctuation => matches!(c, '-' | '_' | '.' | ':'),
+        }
+    }
+}
 
+/// Ownership record of a token, kept apart from its metadata so that
+/// transfers and approvals never have to load or rewrite the extension
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct TokenInfo<T = Empty> {
+pub struct TokenInfo {
     /// The owner of the newly minted NFT
     pub owner: Addr,
     /// Approvals are stored here, as we clear them all upon transfer and cannot accumulate much
     pub approvals: Vec<Approval>,
+}
 
+/// Metadata of a token, written once on mint and removed on burn
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenMetadata<T = Empty> {
     /// Universal resource identifier for this NFT
     /// Should point to a JSON file that conforms to the ERC721
     /// Metadata JSON Schema
@@ -48,31 +326,26 @@ impl Approval {
     }
 }
 
-pub struct TokenIndexes<'a, T>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
+pub struct TokenIndexes<'a> {
     // An owner can have multiple tokens, which has string type TokenPK
-    pub owner: MultiIndex<'a, Addr, TokenInfo<T>, String>,
+    pub owner: MultiIndex<'a, Addr, TokenInfo, String>,
 }
 
-impl<'a, T> IndexList<TokenInfo<T>> for TokenIndexes<'a, T>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
-    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenInfo<T>>> + '_> {
-        let v: Vec<&dyn Index<TokenInfo<T>>> = vec![&self.owner];
+impl<'a> IndexList<TokenInfo> for TokenIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenInfo>> + '_> {
+        // non-enumerable collections never write the owner index
+        #[cfg(not(feature = "non-enumerable"))]
+        let v: Vec<&dyn Index<TokenInfo>> = vec![&self.owner];
+        #[cfg(feature = "non-enumerable")]
+        let v: Vec<&dyn Index<TokenInfo>> = vec![];
         Box::new(v.into_iter())
     }
 }
 
-pub fn get_tokens<'a, T>() -> IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>
-where
-    T: Serialize + DeserializeOwned + Clone,
-{
+pub fn get_tokens<'a>() -> IndexedMap<'a, &'a str, TokenInfo, TokenIndexes<'a>> {
     let indexes = TokenIndexes {
         owner: MultiIndex::new(
-            |d: &TokenInfo<T>| d.owner.clone(),
+            |d: &TokenInfo| d.owner.clone(),
             TOKENS_KEY,
             TOKENS_OWNER_KEY,
         ),
@@ -80,26 +353,372 @@ where
     IndexedMap::new(TOKENS_KEY, indexes)
 }
 
+/// Transfer offered by a sender who can send the token, waiting for the recipient to accept it.
+/// The token stays with its owner until then
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransfer {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub expires: Expiration,
+}
+
+pub struct PendingTransferIndexes<'a> {
+    pub recipient: MultiIndex<'a, Addr, PendingTransfer, String>,
+}
+
+impl<'a> IndexList<PendingTransfer> for PendingTransferIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PendingTransfer>> + '_> {
+        let v: Vec<&dyn Index<PendingTransfer>> = vec![&self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+/// pending transfers by token_id, at most one per token
+pub fn get_pending_transfers<'a>(
+) -> IndexedMap<'a, &'a str, PendingTransfer, PendingTransferIndexes<'a>> {
+    let indexes = PendingTransferIndexes {
+        recipient: MultiIndex::new(
+            |d: &PendingTransfer| d.recipient.clone(),
+            PENDING_TRANSFERS_KEY,
+            PENDING_TRANSFERS_RECIPIENT_KEY,
+        ),
+    };
+    IndexedMap::new(PENDING_TRANSFERS_KEY, indexes)
+}
+
+pub fn load_pending_transfer<E>(
+    storage: &dyn Storage,
+    token_id: &str,
+) -> Result<PendingTransfer, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    get_pending_transfers()
+        .may_load(storage, token_id)?
+        .ok_or_else(|| ContractError::PendingTransferNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// Lock of a token against transfer, only the account that set it can lift it before it expires
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Lock {
+    /// Owner of the token when it was locked, locked tokens can't change owner
+    pub owner: Addr,
+    /// The owner or one of its lock managers
+    pub locked_by: Addr,
+    pub expires: Expiration,
+}
+
+impl Lock {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+pub struct LockIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, Lock, String>,
+}
+
+impl<'a> IndexList<Lock> for LockIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Lock>> + '_> {
+        let v: Vec<&dyn Index<Lock>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// locks by token_id, expired locks are kept until the token moves or is locked again
+pub fn get_locks<'a>() -> IndexedMap<'a, &'a str, Lock, LockIndexes<'a>> {
+    let indexes = LockIndexes {
+        owner: MultiIndex::new(|d: &Lock| d.owner.clone(), LOCKS_KEY, LOCKS_OWNER_KEY),
+    };
+    IndexedMap::new(LOCKS_KEY, indexes)
+}
+
+pub fn get_token_metadata<'a, T>() -> Map<'a, &'a str, TokenMetadata<T>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    Map::new(TOKEN_METADATA_KEY)
+}
+
+pub fn load_token<E>(storage: &dyn Storage, token_id: &str) -> Result<TokenInfo, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
+    get_tokens()
+        .may_load(storage, token_id)?
+        .ok_or_else(|| ContractError::TokenNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+pub fn load_token_metadata<T, E>(
+    storage: &dyn Storage,
+    token_id: &str,
+) -> Result<TokenMetadata<T>, ContractError<E>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: Debug + PartialEq + Error,
+{
+    get_token_metadata()
+        .may_load(storage, token_id)?
+        .ok_or_else(|| ContractError::TokenNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// Token record of cw721-base and of the versions before TOKEN_METADATA,
+/// which kept the metadata with the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyTokenInfo<T> {
+    pub owner: Addr,
+    pub approvals: Vec<Approval>,
+    pub token_uri: Option<String>,
+    pub extension: T,
+}
+
+/// runs the next `limit` tokens of the migration, returns the number of migrated tokens.
+/// Legacy token records get their metadata moved to TOKEN_METADATA and are rewritten as
+/// TokenInfo, tokens that already have metadata are skipped. Collections deployed before
+/// STATS and HOLDER_BALANCES were tracked get them seeded from the same tokens, lifetime
+/// totals start from the live supply. MIGRATION keeps the last token of the batch and is
+/// done once a batch runs out of tokens, a migration started again after that does nothing
+pub fn migrate_token_batch<T>(storage: &mut dyn Storage, limit: u32) -> StdResult<u64>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let mut migration = match MIGRATION.may_load(storage)? {
+        Some(migration) => migration,
+        None => Migration {
+            last_token: None,
+            done: false,
+            seed_stats: STATS.may_load(storage)?.is_none(),
+        },
+    };
+    if migration.done {
+        return Ok(0);
+    }
+
+    let metadata = get_token_metadata::<T>();
+    let start = migration.last_token.as_deref().map(Bound::exclusive);
+    let token_ids = get_tokens()
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+    // the owner is unchanged, the plain map leaves the owner index as it is
+    let legacy_tokens: Map<&str, LegacyTokenInfo<T>> = Map::new(TOKENS_KEY);
+    let tokens: Map<&str, TokenInfo> = Map::new(TOKENS_KEY);
+    let mut stats = get_stats(storage)?;
+
+    let mut migrated = 0;
+    for token_id in &token_ids {
+        let owner = if metadata.has(storage, token_id) {
+            tokens.load(storage, token_id)?.owner
+        } else {
+            let legacy = legacy_tokens.load(storage, token_id)?;
+            metadata.save(
+                storage,
+                token_id,
+                &TokenMetadata {
+                    token_uri: legacy.token_uri,
+                    extension: legacy.extension,
+                },
+            )?;
+            tokens.save(
+                storage,
+                token_id,
+                &TokenInfo {
+                    owner: legacy.owner.clone(),
+                    approvals: legacy.approvals,
+                },
+            )?;
+            migrated += 1;
+            legacy.owner
+        };
+        if migration.seed_stats {
+            stats.minted = checked_inc(stats.minted)?;
+            if add_holder_token(storage, &owner)? {
+                stats.unique_holders = checked_inc(stats.unique_holders)?;
+            }
+        }
+    }
+
+    if migration.seed_stats {
+        STATS.save(storage, &stats)?;
+    }
+    migration.done = token_ids.len() < limit as usize;
+    if let Some(last_token) = token_ids.last() {
+        migration.last_token = Some(last_token.clone());
+    }
+    MIGRATION.save(storage, &migration)?;
+    Ok(migrated)
+}
+
+/// true while the migration has tokens left
+pub fn migration_pending(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(matches!(
+        MIGRATION.may_load(storage)?,
+        Some(Migration { done: false, .. })
+    ))
+}
+
+/// true while the migration is seeding STATS, which only counts the tokens it went through
+pub fn stats_seeding(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(matches!(
+        MIGRATION.may_load(storage)?,
+        Some(Migration {
+            done: false,
+            seed_stats: true,
+            ..
+        })
+    ))
+}
+
 pub fn token_count(storage: &dyn Storage) -> StdResult<u64> {
     Ok(TOKENS_COUNT.may_load(storage)?.unwrap_or_default())
 }
 
 pub fn increment_tokens(storage: &mut dyn Storage) -> StdResult<u64> {
-    let val = token_count(storage)? + 1;
+    let val = token_count(storage)?
+        .checked_add(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, u64::MAX, 1))?;
     TOKENS_COUNT.save(storage, &val)?;
     Ok(val)
 }
 
+/// errors instead of wrapping around when the count is already 0
 pub fn decrement_tokens(storage: &mut dyn Storage) -> StdResult<u64> {
-    let val = token_count(storage)? - 1;
+    let val = token_count(storage)?
+        .checked_sub(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, 0, 1))?;
     TOKENS_COUNT.save(storage, &val)?;
     Ok(val)
 }
 
-pub fn set_contract_info(
+pub fn get_stats(storage: &dyn Storage) -> StdResult<Stats> {
+    Ok(STATS.may_load(storage)?.unwrap_or_default())
+}
+
+fn checked_inc(val: u64) -> StdResult<u64> {
+    Ok(val
+        .checked_add(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, val, 1))?)
+}
+
+fn checked_dec(val: u64) -> StdResult<u64> {
+    Ok(val
+        .checked_sub(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, val, 1))?)
+}
+
+/// adds a token to the balance of the holder, returns true for a new holder
+fn add_holder_token(storage: &mut dyn Storage, holder: &Addr) -> StdResult<bool> {
+    let balance = HOLDER_BALANCES
+        .may_load(storage, holder)?
+        .unwrap_or_default();
+    HOLDER_BALANCES.save(storage, holder, &checked_inc(balance)?)?;
+    Ok(balance == 0)
+}
+
+/// removes a token from the balance of the holder, returns true when it was the last one
+fn remove_holder_token(storage: &mut dyn Storage, holder: &Addr) -> StdResult<bool> {
+    let balance = checked_dec(
+        HOLDER_BALANCES
+            .may_load(storage, holder)?
+            .unwrap_or_default(),
+    )?;
+    if balance == 0 {
+        HOLDER_BALANCES.remove(storage, holder);
+    } else {
+        HOLDER_BALANCES.save(storage, holder, &balance)?;
+    }
+    Ok(balance == 0)
+}
+
+pub fn record_mint(storage: &mut dyn Storage, owner: &Addr, time: Timestamp) -> StdResult<()> {
+    let mut stats = get_stats(storage)?;
+    stats.minted = checked_inc(stats.minted)?;
+    stats.last_mint = Some(time);
+    if add_holder_token(storage, owner)? {
+        stats.unique_holders = checked_inc(stats.unique_holders)?;
+    }
+    STATS.save(storage, &stats)
+}
+
+pub fn record_burn(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
+    let mut stats = get_stats(storage)?;
+    stats.burned = checked_inc(stats.burned)?;
+    if remove_holder_token(storage, owner)? {
+        stats.unique_holders = checked_dec(stats.unique_holders)?;
+    }
+    STATS.save(storage, &stats)
+}
+
+pub fn record_transfer(storage: &mut dyn Storage, from: &Addr, to: &Addr) -> StdResult<()> {
+    let mut stats = get_stats(storage)?;
+    stats.transferred = checked_inc(stats.transferred)?;
+    if remove_holder_token(storage, from)? {
+        stats.unique_holders = checked_dec(stats.unique_holders)?;
+    }
+    if add_holder_token(storage, to)? {
+        stats.unique_holders = checked_inc(stats.unique_holders)?;
+    }
+    STATS.save(storage, &stats)
+}
+
+pub fn get_address_list_mode(storage: &dyn Storage) -> StdResult<AddressListMode> {
+    Ok(ADDRESS_LIST_MODE.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn get_history_cap(storage: &dyn Storage) -> StdResult<u32> {
+    Ok(HISTORY_CAP
+        .may_load(storage)?
+        .unwrap_or(DEFAULT_HISTORY_CAP))
+}
+
+/// appends the entry to the history of the token and prunes the entries past the cap,
+/// a cap of 0 disables the history
+pub fn append_history(
+    storage: &mut dyn Storage,
+    token_id: &str,
+    entry: &HistoryEntry,
+) -> StdResult<()> {
+    let cap = get_history_cap(storage)? as u64;
+    if cap == 0 {
+        return Ok(());
+    }
+
+    let history = TOKEN_HISTORY.prefix(token_id);
+    let seq = match history.keys(storage, None, None, Order::Descending).next() {
+        Some(last) => checked_inc(last?)?,
+        None => 0,
+    };
+    TOKEN_HISTORY.save(storage, (token_id, seq), entry)?;
+
+    if let Some(oldest_kept) = (seq + 1).checked_sub(cap) {
+        let pruned: Vec<u64> = history
+            .keys(
+                storage,
+                None,
+                Some(Bound::exclusive(oldest_kept)),
+                Order::Ascending,
+            )
+            .collect::<StdResult<_>>()?;
+        for seq in pruned {
+            TOKEN_HISTORY.remove(storage, (token_id, seq));
+        }
+    }
+    Ok(())
+}
+
+pub fn set_contract_info<E>(
     storage: &mut dyn Storage,
     info: ContractInfoResponse,
-) -> Result<Response, ContractError> {
+) -> Result<Response, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
     let res = CONTRACT_INFO.save(storage, &info);
     match res {
         Ok(_) => Ok(Response::new()),
@@ -107,7 +726,10 @@ pub fn set_contract_info(
     }
 }
 
-pub fn set_minter(storage: &mut dyn Storage, minter: Addr) -> Result<Response, ContractError> {
+pub fn set_minter<E>(storage: &mut dyn Storage, minter: Addr) -> Result<Response, ContractError<E>>
+where
+    E: Debug + PartialEq + Error,
+{
     let res = MINTER.save(storage, &minter);
     match res {
         Ok(_) => Ok(Response::new()),
@@ -119,13 +741,30 @@ pub fn get_minter(storage: &dyn Storage) -> Addr {
     MINTER.load(storage).unwrap_or_else(|_| Addr::unchecked(""))
 }
 
+pub fn allow_remint(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(ALLOW_REMINT.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn get_token_id_policy(storage: &dyn Storage) -> StdResult<TokenIdPolicy> {
+    Ok(TOKEN_ID_POLICY.may_load(storage)?.unwrap_or_default())
+}
+
+/// the admin defaults to the minter
+pub fn get_admin(storage: &dyn Storage) -> StdResult<Addr> {
+    Ok(ADMIN
+        .may_load(storage)?
+        .unwrap_or_else(|| get_minter(storage)))
+}
+
 #[cfg(test)]
 mod state_tests {
     use crate::error::{ContractError, CustomError};
-    use crate::state::get_tokens;
-    use crate::state::token_count;
-    use crate::state::{decrement_tokens, increment_tokens, TokenInfo};
-    use cosmwasm_std::{Addr, Empty};
+    use crate::state::{decrement_tokens, increment_tokens, TokenInfo, TokenMetadata};
+    use crate::state::{get_token_metadata, get_tokens, token_count};
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::{
+        Addr, Empty, Order, OverflowError, OverflowOperation, Record, StdError, Storage,
+    };
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
@@ -146,9 +785,12 @@ mod state_tests {
             url: String,
         }
 
-        let new_token = TokenInfo::<CustomInfo> {
+        let new_token = TokenInfo {
             owner: owner_addr.clone(),
             approvals: vec![],
+        };
+
+        let new_token_metadata = TokenMetadata::<CustomInfo> {
             token_uri: None,
             extension: CustomInfo {
                 name: "test_nft".to_string(),
@@ -156,9 +798,7 @@ mod state_tests {
             },
         };
 
-        let empty_custom_token = TokenInfo::<Empty> {
-            owner: owner_addr.clone(),
-            approvals: vec![],
+        let empty_custom_token_metadata = TokenMetadata::<Empty> {
             token_uri: None,
             extension: Empty {},
         };
@@ -170,6 +810,9 @@ mod state_tests {
                 None => Ok(new_token.clone()),
             })
             .unwrap();
+        get_token_metadata()
+            .save(&mut deps.storage, token_1_id, &new_token_metadata)
+            .unwrap();
 
         let token_count_after_increment_1 = increment_tokens(&mut deps.storage).unwrap_or_default();
         assert_eq!(token_count_after_increment_1, 1);
@@ -177,9 +820,12 @@ mod state_tests {
         get_tokens()
             .update(&mut deps.storage, token_2_id, |old| match old {
                 Some(_) => Err(ContractError::<CustomError>::Claimed {}),
-                None => Ok(empty_custom_token),
+                None => Ok(new_token.clone()),
             })
             .unwrap();
+        get_token_metadata()
+            .save(&mut deps.storage, token_2_id, &empty_custom_token_metadata)
+            .unwrap();
 
         // Minting nft with same id will fail
         let wrong_token_update =
@@ -193,18 +839,92 @@ mod state_tests {
         let token_count_after_increment_2 = increment_tokens(&mut deps.storage).unwrap_or_default();
         assert_eq!(token_count_after_increment_2, 2);
 
-        let token_1: TokenInfo<CustomInfo> = get_tokens().load(&deps.storage, token_1_id).unwrap();
-        let token_2: TokenInfo<Empty> = get_tokens().load(&deps.storage, token_2_id).unwrap();
+        let token_1 = get_tokens().load(&deps.storage, token_1_id).unwrap();
+        let token_1_metadata: TokenMetadata<CustomInfo> = get_token_metadata()
+            .load(&deps.storage, token_1_id)
+            .unwrap();
+        let token_2 = get_tokens().load(&deps.storage, token_2_id).unwrap();
+        let token_2_metadata: TokenMetadata<Empty> = get_token_metadata()
+            .load(&deps.storage, token_2_id)
+            .unwrap();
 
         assert_eq!(token_1.owner, owner_addr);
-        assert_eq!(token_1.extension.name, "test_nft");
+        assert_eq!(token_1_metadata.extension.name, "test_nft");
         assert_eq!(token_2.owner, owner_addr);
-        assert_eq!(token_2.extension, Empty {});
+        assert_eq!(token_2_metadata.extension, Empty {});
 
         let count = token_count(&deps.storage).unwrap_or_default();
         assert_eq!(count, 2);
 
         let token_count_after_decrement = decrement_tokens(&mut deps.storage).unwrap_or_default();
         assert_eq!(token_count_after_decrement, 1);
+
+        // count never wraps around below 0
+        decrement_tokens(&mut deps.storage).unwrap();
+        let underflow = decrement_tokens(&mut deps.storage).unwrap_err();
+        assert_eq!(
+            underflow,
+            StdError::overflow(OverflowError::new(OverflowOperation::Sub, 0, 1))
+        );
+        assert_eq!(token_count(&deps.storage).unwrap(), 0);
+    }
+
+    /// MockStorage counting set and remove calls
+    #[derive(Default)]
+    struct WriteCountingStorage {
+        inner: MockStorage,
+        writes: usize,
+    }
+
+    impl Storage for WriteCountingStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.inner.get(key)
+        }
+
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.inner.range(start, end, order)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.writes += 1;
+            self.inner.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.writes += 1;
+            self.inner.remove(key)
+        }
+    }
+
+    #[test]
+    fn test_owner_index_writes() {
+        let mut storage = WriteCountingStorage::default();
+        let mut writes = |op: &dyn Fn(&mut dyn Storage)| {
+            let before = storage.writes;
+            op(&mut storage);
+            storage.writes - before
+        };
+        let token = |owner: &str| TokenInfo {
+            owner: Addr::unchecked(owner),
+            approvals: vec![],
+        };
+
+        let mint = writes(&|storage| get_tokens().save(storage, "1", &token("ADDR1")).unwrap());
+        // approvals and transfers rewrite the owner index even when the owner doesn't change
+        let approve = writes(&|storage| get_tokens().save(storage, "1", &token("ADDR1")).unwrap());
+        let transfer = writes(&|storage| get_tokens().save(storage, "1", &token("ADDR2")).unwrap());
+        let burn = writes(&|storage| get_tokens().remove(storage, "1").unwrap());
+
+        // the owner index costs one write per mint and burn, two per approval and transfer
+        if cfg!(feature = "non-enumerable") {
+            assert_eq!((mint, approve, transfer, burn), (1, 1, 1, 1));
+        } else {
+            assert_eq!((mint, approve, transfer, burn), (2, 3, 3, 2));
+        }
     }
 }
@@ -0,0 +1,496 @@
+use crate::contract::{instantiate, query};
+use crate::error::CustomError;
+use crate::extension::Cw721Extension;
+use crate::msg::InstantiateMsg;
+use crate::state::{
+    append_history, decrement_tokens, get_token_metadata, get_tokens, increment_tokens, load_token,
+    record_burn, record_mint, record_transfer, Approval, HistoryAction, HistoryEntry, TokenInfo,
+    TokenMetadata, BURNED_TOKENS, OPERATORS,
+};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, Addr, ContractResult, CustomQuery, Deps, DepsMut, Empty, QuerierResult,
+    QuerierWrapper, Storage, SystemError, SystemResult, WasmQuery,
+};
+use cw721::Expiration;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Instantiate message of the tests with every option unset, tests override the fields they need:
+///
+/// ```ignore
+/// InstantiateMsg {
+///     minter: "minter".to_string(),
+///     allow_remint: true,
+///     ..Default::default()
+/// }
+/// ```
+impl<T> Default for InstantiateMsg<T> {
+    fn default() -> Self {
+        InstantiateMsg {
+            name: "cw721-contract".to_string(),
+            symbol: "cw721".to_string(),
+            minter: "minter".to_string(),
+            admin: None,
+            collection_info: None,
+            token_id_policy: None,
+            initial_mints: vec![],
+            allow_remint: false,
+            history_cap: None,
+            transfer_fee: None,
+        }
+    }
+}
+
+/// Fake collection for unit tests of contracts querying a cw721-simple-base collection.
+/// Tokens, approvals and operators are written straight into an in-memory storage with the
+/// layout of state.rs, and smart queries are answered by contract::query, so responses are the
+/// same as the ones of a deployed collection.
+///
+/// ```ignore
+/// let collection = MockCw721Querier::<Extension>::new("collection", "minter");
+/// collection.mint("1", "owner", None, None);
+/// collection.install(&mut deps.querier);
+/// ```
+pub struct MockCw721Querier<T = Option<Empty>> {
+    contract_addr: String,
+    storage: Rc<RefCell<MockStorage>>,
+    extension: PhantomData<T>,
+}
+
+// derive(Clone) would require T: Clone, clones share the same storage
+impl<T> Clone for MockCw721Querier<T> {
+    fn clone(&self) -> Self {
+        MockCw721Querier {
+            contract_addr: self.contract_addr.clone(),
+            storage: self.storage.clone(),
+            extension: PhantomData,
+        }
+    }
+}
+
+/// Cw721Extension answering the base queries for metadata T
+struct MockExtension<T>(PhantomData<T>);
+
+impl<T> Cw721Extension for MockExtension<T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    type Extension = T;
+    type ExecuteMsg = Empty;
+    type QueryMsg = Empty;
+    type CustomMsg = Empty;
+    type Error = CustomError;
+}
+
+impl<T> MockCw721Querier<T>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    pub fn new(contract_addr: impl Into<String>, minter: impl Into<String>) -> Self {
+        let contract_addr = contract_addr.into();
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        let querier = MockQuerier::<Empty>::new(&[]);
+        instantiate::<T, Empty, CustomError>(
+            DepsMut {
+                storage: &mut storage,
+                api: &api,
+                querier: QuerierWrapper::new(&querier),
+            },
+            mock_env(),
+            mock_info(&contract_addr, &[]),
+            InstantiateMsg {
+                name: contract_addr.clone(),
+                symbol: "MOCK".to_string(),
+                minter: minter.into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        MockCw721Querier {
+            contract_addr,
+            storage: Rc::new(RefCell::new(storage)),
+            extension: PhantomData,
+        }
+    }
+
+    pub fn contract_addr(&self) -> &str {
+        &self.contract_addr
+    }
+
+    /// stores the token, replacing any token with the same id,
+    /// the stats and the history count a replaced token as transferred to the new owner
+    pub fn mint(&self, token_id: &str, owner: &str, token_uri: Option<String>, extension: T) {
+        let storage: &mut dyn Storage = &mut *self.storage.borrow_mut();
+        let owner = Addr::unchecked(owner);
+        let entry = match get_tokens().may_load(storage, token_id).unwrap() {
+            Some(old) => {
+                record_transfer(storage, &old.owner, &owner).unwrap();
+                HistoryEntry::new(
+                    &mock_env().block,
+                    Some(old.owner),
+                    Some(owner.clone()),
+                    HistoryAction::Transfer,
+                )
+            }
+            None => {
+                increment_tokens(storage).unwrap();
+                record_mint(storage, &owner, mock_env().block.time).unwrap();
+                HistoryEntry::new(
+                    &mock_env().block,
+                    None,
+                    Some(owner.clone()),
+                    HistoryAction::Mint,
+                )
+            }
+        };
+        append_history(storage, token_id, &entry).unwrap();
+        get_tokens()
+            .save(
+                storage,
+                token_id,
+                &TokenInfo {
+                    owner,
+                    approvals: vec![],
+                },
+            )
+            .unwrap();
+        get_token_metadata()
+            .save(
+                storage,
+                token_id,
+                &TokenMetadata {
+                    token_uri,
+                    extension,
+                },
+            )
+            .unwrap();
+    }
+
+    /// moves the token to the recipient and clears its approvals
+    pub fn transfer(&self, token_id: &str, recipient: &str) {
+        let recipient = Addr::unchecked(recipient);
+        let owner = load_token::<CustomError>(&*self.storage.borrow(), token_id)
+            .unwrap()
+            .owner;
+        let entry = HistoryEntry::new(
+            &mock_env().block,
+            Some(owner.clone()),
+            Some(recipient.clone()),
+            HistoryAction::Transfer,
+        );
+        record_transfer(&mut *self.storage.borrow_mut(), &owner, &recipient).unwrap();
+        append_history(&mut *self.storage.borrow_mut(), token_id, &entry).unwrap();
+        self.update_token(token_id, |token| {
+            token.owner = recipient;
+            token.approvals = vec![];
+        });
+    }
+
+    pub fn approve(&self, token_id: &str, spender: &str, expires: Expiration) {
+        self.update_token(token_id, |token| {
+            token.approvals.retain(|apr| apr.spender != spender);
+            token.approvals.push(Approval {
+                spender: Addr::unchecked(spender),
+                expires,
+            });
+        });
+    }
+
+    pub fn revoke(&self, token_id: &str, spender: &str) {
+        self.update_token(token_id, |token| {
+            token.approvals.retain(|apr| apr.spender != spender)
+        });
+    }
+
+    pub fn approve_all(&self, owner: &str, operator: &str, expires: Expiration) {
+        OPERATORS
+            .save(
+                &mut *self.storage.borrow_mut(),
+                (&Addr::unchecked(owner), &Addr::unchecked(operator)),
+                &expires,
+            )
+            .unwrap();
+    }
+
+    pub fn revoke_all(&self, owner: &str, operator: &str) {
+        OPERATORS.remove(
+            &mut *self.storage.borrow_mut(),
+            (&Addr::unchecked(owner), &Addr::unchecked(operator)),
+        );
+    }
+
+    pub fn burn(&self, token_id: &str) {
+        let storage: &mut dyn Storage = &mut *self.storage.borrow_mut();
+        let token = load_token::<CustomError>(storage, token_id).unwrap();
+        get_tokens().remove(storage, token_id).unwrap();
+        get_token_metadata::<T>().remove(storage, token_id);
+        BURNED_TOKENS.save(storage, token_id, &Empty {}).unwrap();
+        decrement_tokens(storage).unwrap();
+        record_burn(storage, &token.owner).unwrap();
+        let entry = HistoryEntry::new(
+            &mock_env().block,
+            Some(token.owner),
+            None,
+            HistoryAction::Burn,
+        );
+        append_history(storage, token_id, &entry).unwrap();
+    }
+
+    fn update_token(&self, token_id: &str, update: impl FnOnce(&mut TokenInfo)) {
+        let storage: &mut dyn Storage = &mut *self.storage.borrow_mut();
+        let mut token = load_token::<CustomError>(storage, token_id).unwrap();
+        update(&mut token);
+        get_tokens().save(storage, token_id, &token).unwrap();
+    }
+
+    /// answers smart and raw queries sent to this collection
+    pub fn handle(&self, request: &WasmQuery) -> QuerierResult {
+        let storage = self.storage.borrow();
+        match request {
+            WasmQuery::Smart { contract_addr, msg } if *contract_addr == self.contract_addr => {
+                let api = MockApi::default();
+                let querier = MockQuerier::<Empty>::new(&[]);
+                let deps = Deps {
+                    storage: &*storage,
+                    api: &api,
+                    querier: QuerierWrapper::new(&querier),
+                };
+                let res = from_binary(msg)
+                    .and_then(|msg| query(deps, mock_env(), msg, &MockExtension::<T>(PhantomData)));
+                SystemResult::Ok(ContractResult::from(res))
+            }
+            WasmQuery::Raw { contract_addr, key } if *contract_addr == self.contract_addr => {
+                let value = storage.get(key).unwrap_or_default();
+                SystemResult::Ok(ContractResult::Ok(value.into()))
+            }
+            WasmQuery::Smart { contract_addr, .. } | WasmQuery::Raw { contract_addr, .. } => {
+                SystemResult::Err(SystemError::NoSuchContract {
+                    addr: contract_addr.clone(),
+                })
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        }
+    }
+
+    /// routes the wasm queries of the querier to this collection,
+    /// tokens minted after install are visible as well
+    pub fn install<C>(&self, querier: &mut MockQuerier<C>)
+    where
+        C: CustomQuery + DeserializeOwned,
+    {
+        let collection = self.clone();
+        querier.update_wasm(move |request| collection.handle(request));
+    }
+}
+
+/// Collection and helpers shared by the unit tests of the crate
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::contract::{execute, instantiate};
+    use crate::error::{ContractError, CustomError};
+    use crate::extension::Cw721Extension;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{DepsMut, Empty, Response};
+    use cw721::Expiration;
+
+    pub const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+    pub const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+
+    pub type Extension = Option<Empty>;
+
+    pub struct TestExtension;
+
+    impl Cw721Extension for TestExtension {
+        type Extension = Extension;
+        type ExecuteMsg = Empty;
+        type QueryMsg = Empty;
+        type CustomMsg = Empty;
+        type Error = CustomError;
+    }
+
+    pub fn init(deps: DepsMut) {
+        instantiate::<Extension, Empty, CustomError>(
+            deps,
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            InstantiateMsg {
+                minter: ADDR1.to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
+    pub fn mint(deps: DepsMut, owner: &str, token_id: &str) -> Result<Response, ContractError> {
+        let execute_mint_msg = ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        });
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            execute_mint_msg,
+            &TestExtension,
+        )
+    }
+
+    pub fn approve(deps: DepsMut, sender: &str, spender: &str) -> Result<Response, ContractError> {
+        let valid_approve_msg = ExecuteMsg::Approve {
+            spender: spender.to_string(),
+            token_id: "1".to_string(),
+            expires: Some(Expiration::AtHeight(50000)),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            valid_approve_msg,
+            &TestExtension,
+        )
+    }
+
+    pub fn transfer_nft(
+        deps: DepsMut,
+        sender: &str,
+        recipient: &str,
+    ) -> Result<Response, ContractError> {
+        let transfer_nft_msg = ExecuteMsg::<Extension, Empty>::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: "1".to_string(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            transfer_nft_msg,
+            &TestExtension,
+        )
+    }
+
+    pub fn burn(deps: DepsMut, token_id: &str) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            ExecuteMsg::Burn {
+                token_id: token_id.to_string(),
+            },
+            &TestExtension,
+        )
+    }
+}
+
+#[cfg(test)]
+mod testing_tests {
+    use crate::msg::QueryMsg;
+    use crate::testing::MockCw721Querier;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{to_binary, Empty, QueryRequest, StdError, WasmQuery};
+    use cw721::{Approval, Expiration, NftInfoResponse, NumTokensResponse, OwnerOfResponse};
+
+    const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+    const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+
+    #[test]
+    fn test_mock_querier() {
+        let mut deps = mock_dependencies();
+        let collection = MockCw721Querier::<Option<String>>::new("collection", ADDR1);
+        collection.install(&mut deps.querier);
+
+        // tokens written after install are answered as well
+        collection.mint("1", ADDR1, None, Some("metadata".to_string()));
+        collection.mint("2", ADDR1, None, None);
+        collection.approve("1", ADDR2, Expiration::Never {});
+
+        let owner_of: OwnerOfResponse = deps
+            .as_ref()
+            .querier
+            .query_wasm_smart(
+                "collection",
+                &QueryMsg::<Empty>::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            owner_of,
+            OwnerOfResponse {
+                owner: ADDR1.to_string(),
+                approvals: vec![Approval {
+                    spender: ADDR2.to_string(),
+                    expires: Expiration::Never {},
+                }],
+            }
+        );
+
+        let nft_info: NftInfoResponse<Option<String>> = deps
+            .as_ref()
+            .querier
+            .query_wasm_smart(
+                "collection",
+                &QueryMsg::<Empty>::NftInfo {
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(nft_info.extension, Some("metadata".to_string()));
+
+        collection.transfer("1", ADDR2);
+        collection.burn("2");
+        let num_tokens: NumTokensResponse = deps
+            .as_ref()
+            .querier
+            .query_wasm_smart("collection", &QueryMsg::<Empty>::NumTokens {})
+            .unwrap();
+        assert_eq!(num_tokens.count, 1);
+
+        // errors of contract::query are returned as contract errors
+        let err = deps
+            .as_ref()
+            .querier
+            .query_wasm_smart::<OwnerOfResponse>(
+                "collection",
+                &QueryMsg::<Empty>::OwnerOf {
+                    token_id: "2".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(
+                "Querier contract error: cw721_simple_base::state::TokenInfo not found"
+            )
+        );
+
+        // other contracts are unknown
+        let err = deps
+            .as_ref()
+            .querier
+            .query::<OwnerOfResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: "other".to_string(),
+                msg: to_binary(&QueryMsg::<Empty>::NumTokens {}).unwrap(),
+            }))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Querier system error: No such contract: other")
+        );
+    }
+}
@@ -0,0 +1,720 @@
+//! cw721 conformance suite for contracts built on cw721-simple-base
+//!
+//! Pass your own entry points and a few extension samples, then run every check:
+//!
+//! ```ignore
+//! Cw721Suite {
+//!     instantiate,
+//!     execute,
+//!     query,
+//!     extensions: vec![None, Some(Metadata { .. })],
+//! }
+//! .run_all();
+//! ```
+//!
+//! Messages are built as cw721-base messages and converted through JSON, so the entry points can
+//! take the cw721-simple-base messages as well as your own enums flattening them.
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
+    OwnedDeps, Response, StdResult, WasmMsg,
+};
+use cw721::{
+    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
+    Cw721ReceiveMsg, Expiration, NftInfoResponse, NumTokensResponse, OperatorsResponse,
+    OwnerOfResponse, TokensResponse,
+};
+use cw721_base::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+
+pub const MINTER: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+pub const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+pub const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+pub const ADDR3: &str = "cosmos18zfp9u7zxg3gel4r3txa2jqxme7jkw7dka7zjv";
+
+const NAME: &str = "cw721-contract";
+const SYMBOL: &str = "cw721";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+/// Entry points of the contract under test.
+/// I, X and Q are the instantiate, execute and query messages of the contract, they must accept
+/// the JSON of the cw721-base messages.
+pub struct Cw721Suite<T, I, X, Q, C, E> {
+    pub instantiate: fn(DepsMut, Env, MessageInfo, I) -> Result<Response<C>, E>,
+    pub execute: fn(DepsMut, Env, MessageInfo, X) -> Result<Response<C>, E>,
+    pub query: fn(Deps, Env, Q) -> StdResult<Binary>,
+    /// Extensions minted with the tokens, used round-robin, at least one
+    pub extensions: Vec<T>,
+}
+
+impl<T, I, X, Q, C, E> Cw721Suite<T, I, X, Q, C, E>
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + Debug,
+    I: DeserializeOwned,
+    X: DeserializeOwned,
+    Q: DeserializeOwned,
+    C: Clone + Debug + PartialEq,
+    E: Display + Debug,
+{
+    pub fn run_all(&self) {
+        assert!(
+            !self.extensions.is_empty(),
+            "at least one extension sample is required"
+        );
+        self.check_contract_info();
+        self.check_mint();
+        self.check_approve();
+        self.check_revoke();
+        self.check_operator();
+        self.check_transfer();
+        self.check_send();
+        self.check_burn();
+        self.check_pagination();
+    }
+
+    fn init(&self) -> MockDeps {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            name: NAME.to_string(),
+            symbol: SYMBOL.to_string(),
+            minter: MINTER.to_string(),
+        };
+        let msg: I = from_binary(&to_binary(&msg).unwrap()).unwrap();
+        (self.instantiate)(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn extension(&self, index: usize) -> T {
+        self.extensions[index % self.extensions.len()].clone()
+    }
+
+    fn execute(
+        &self,
+        deps: &mut MockDeps,
+        sender: &str,
+        msg: ExecuteMsg<T, Empty>,
+    ) -> Result<Response<C>, E> {
+        let msg: X = from_binary(&to_binary(&msg).unwrap()).unwrap();
+        (self.execute)(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn query<R: DeserializeOwned>(&self, deps: &MockDeps, msg: QueryMsg<Empty>) -> StdResult<R> {
+        let msg: Q = from_binary(&to_binary(&msg).unwrap()).unwrap();
+        (self.query)(deps.as_ref(), mock_env(), msg).and_then(|res| from_binary(&res))
+    }
+
+    fn mint(&self, deps: &mut MockDeps, owner: &str, token_id: &str) -> Result<Response<C>, E> {
+        let index = token_id.parse().unwrap_or_default();
+        self.execute(
+            deps,
+            MINTER,
+            ExecuteMsg::Mint(MintMsg {
+                token_id: token_id.to_string(),
+                owner: owner.to_string(),
+                token_uri: Some(format!("https://example.com/{}", token_id)),
+                extension: self.extension(index),
+            }),
+        )
+    }
+
+    fn approve(
+        &self,
+        deps: &mut MockDeps,
+        sender: &str,
+        spender: &str,
+        token_id: &str,
+        expires: Expiration,
+    ) -> Result<Response<C>, E> {
+        self.execute(
+            deps,
+            sender,
+            ExecuteMsg::Approve {
+                spender: spender.to_string(),
+                token_id: token_id.to_string(),
+                expires: Some(expires),
+            },
+        )
+    }
+
+    fn transfer(
+        &self,
+        deps: &mut MockDeps,
+        sender: &str,
+        recipient: &str,
+        token_id: &str,
+    ) -> Result<Response<C>, E> {
+        self.execute(
+            deps,
+            sender,
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            },
+        )
+    }
+
+    fn owner_of(&self, deps: &MockDeps, token_id: &str) -> OwnerOfResponse {
+        self.query(
+            deps,
+            QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: Some(true),
+            },
+        )
+        .unwrap()
+    }
+
+    fn num_tokens(&self, deps: &MockDeps) -> u64 {
+        let res: NumTokensResponse = self.query(deps, QueryMsg::NumTokens {}).unwrap();
+        res.count
+    }
+
+    pub fn check_contract_info(&self) {
+        let deps = self.init();
+
+        let info: ContractInfoResponse = self.query(&deps, QueryMsg::ContractInfo {}).unwrap();
+        assert_eq!(
+            info,
+            ContractInfoResponse {
+                name: NAME.to_string(),
+                symbol: SYMBOL.to_string(),
+            }
+        );
+
+        let minter: MinterResponse = self.query(&deps, QueryMsg::Minter {}).unwrap();
+        assert_eq!(
+            minter,
+            MinterResponse {
+                minter: MINTER.to_string()
+            }
+        );
+        assert_eq!(self.num_tokens(&deps), 0);
+    }
+
+    pub fn check_mint(&self) {
+        let mut deps = self.init();
+
+        let res = self.mint(&mut deps, ADDR1, "1").unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "mint"),
+                attr("minter", MINTER),
+                attr("owner", ADDR1),
+                attr("token_id", "1"),
+            ]
+        );
+        self.mint(&mut deps, ADDR2, "2").unwrap();
+        assert_eq!(self.num_tokens(&deps), 2);
+
+        // token_id can't be claimed twice
+        let claimed = self.mint(&mut deps, ADDR2, "1").unwrap_err();
+        assert_eq!(claimed.to_string(), "token_id already claimed");
+
+        // only the minter can mint
+        let unauthorized = self
+            .execute(
+                &mut deps,
+                ADDR2,
+                ExecuteMsg::Mint(MintMsg {
+                    token_id: "3".to_string(),
+                    owner: ADDR2.to_string(),
+                    token_uri: None,
+                    extension: self.extension(3),
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+        assert_eq!(self.num_tokens(&deps), 2);
+
+        for (index, token_id) in ["1", "2"].iter().enumerate() {
+            let info: NftInfoResponse<T> = self
+                .query(
+                    &deps,
+                    QueryMsg::NftInfo {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                info,
+                NftInfoResponse {
+                    token_uri: Some(format!("https://example.com/{}", token_id)),
+                    extension: self.extension(index + 1),
+                }
+            );
+        }
+
+        let all_info: AllNftInfoResponse<T> = self
+            .query(
+                &deps,
+                QueryMsg::AllNftInfo {
+                    token_id: "2".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            all_info,
+            AllNftInfoResponse {
+                access: OwnerOfResponse {
+                    owner: ADDR2.to_string(),
+                    approvals: vec![],
+                },
+                info: NftInfoResponse {
+                    token_uri: Some("https://example.com/2".to_string()),
+                    extension: self.extension(2),
+                },
+            }
+        );
+    }
+
+    pub fn check_approve(&self) {
+        let mut deps = self.init();
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+
+        let res = self
+            .approve(&mut deps, ADDR1, ADDR2, "1", Expiration::AtHeight(50000))
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "approve"),
+                attr("sender", ADDR1),
+                attr("spender", ADDR2),
+                attr("token_id", "1"),
+            ]
+        );
+
+        let approval: ApprovalResponse = self
+            .query(
+                &deps,
+                QueryMsg::Approval {
+                    token_id: "1".to_string(),
+                    spender: ADDR2.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            approval,
+            ApprovalResponse {
+                approval: Approval {
+                    spender: ADDR2.to_string(),
+                    expires: Expiration::AtHeight(50000),
+                }
+            }
+        );
+
+        let approvals: ApprovalsResponse = self
+            .query(
+                &deps,
+                QueryMsg::Approvals {
+                    token_id: "1".to_string(),
+                    include_expired: Some(true),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            approvals,
+            ApprovalsResponse {
+                approvals: vec![Approval {
+                    spender: ADDR2.to_string(),
+                    expires: Expiration::AtHeight(50000),
+                }]
+            }
+        );
+
+        let expired = self
+            .approve(&mut deps, ADDR1, ADDR2, "1", Expiration::AtHeight(100))
+            .unwrap_err();
+        assert_eq!(
+            expired.to_string(),
+            "Cannot set approval that is already expired"
+        );
+
+        let unauthorized = self
+            .approve(&mut deps, ADDR2, ADDR2, "1", Expiration::AtHeight(50000))
+            .unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+
+        // approved spender can transfer, approvals are cleared afterwards
+        self.transfer(&mut deps, ADDR2, ADDR3, "1").unwrap();
+        assert_eq!(
+            self.owner_of(&deps, "1"),
+            OwnerOfResponse {
+                owner: ADDR3.to_string(),
+                approvals: vec![],
+            }
+        );
+    }
+
+    pub fn check_revoke(&self) {
+        let mut deps = self.init();
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+        self.approve(&mut deps, ADDR1, ADDR2, "1", Expiration::AtHeight(50000))
+            .unwrap();
+
+        let res = self
+            .execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::Revoke {
+                    spender: ADDR2.to_string(),
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "revoke"),
+                attr("sender", ADDR1),
+                attr("spender", ADDR2),
+                attr("token_id", "1"),
+            ]
+        );
+        assert_eq!(self.owner_of(&deps, "1").approvals, vec![]);
+
+        // revoked spender can't transfer anymore
+        let unauthorized = self.transfer(&mut deps, ADDR2, ADDR2, "1").unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+    }
+
+    pub fn check_operator(&self) {
+        let mut deps = self.init();
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+        self.mint(&mut deps, ADDR1, "2").unwrap();
+
+        let res = self
+            .execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::ApproveAll {
+                    operator: ADDR2.to_string(),
+                    expires: Some(Expiration::AtHeight(50000)),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "approve_all"),
+                attr("sender", ADDR1),
+                attr("operator", ADDR2),
+            ]
+        );
+
+        let operators: OperatorsResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllOperators {
+                    owner: ADDR1.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            operators,
+            OperatorsResponse {
+                operators: vec![Approval {
+                    spender: ADDR2.to_string(),
+                    expires: Expiration::AtHeight(50000),
+                }]
+            }
+        );
+
+        let expired = self
+            .execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::ApproveAll {
+                    operator: ADDR2.to_string(),
+                    expires: Some(Expiration::AtHeight(10)),
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            expired.to_string(),
+            "Cannot set approval that is already expired"
+        );
+
+        // operator can approve and transfer every token of the owner
+        self.approve(&mut deps, ADDR2, ADDR3, "1", Expiration::Never {})
+            .unwrap();
+        self.transfer(&mut deps, ADDR2, ADDR3, "2").unwrap();
+        assert_eq!(self.owner_of(&deps, "2").owner, ADDR3);
+
+        let res = self
+            .execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::RevokeAll {
+                    operator: ADDR2.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "revoke_all"),
+                attr("sender", ADDR1),
+                attr("operator", ADDR2),
+            ]
+        );
+
+        let unauthorized = self.transfer(&mut deps, ADDR2, ADDR2, "1").unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+    }
+
+    pub fn check_transfer(&self) {
+        let mut deps = self.init();
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+
+        let res = self.transfer(&mut deps, ADDR1, ADDR2, "1").unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "transfer_nft"),
+                attr("sender", ADDR1),
+                attr("recipient", ADDR2),
+                attr("token_id", "1"),
+            ]
+        );
+        assert_eq!(
+            self.owner_of(&deps, "1"),
+            OwnerOfResponse {
+                owner: ADDR2.to_string(),
+                approvals: vec![],
+            }
+        );
+
+        // previous owner is unauthorized
+        let unauthorized = self.transfer(&mut deps, ADDR1, ADDR2, "1").unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+
+        self.mint(&mut deps, ADDR2, "2").unwrap();
+        let tokens: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::Tokens {
+                    owner: ADDR2.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(tokens.tokens, vec!["1".to_string(), "2".to_string()]);
+
+        // metadata moves with the token
+        let info: NftInfoResponse<T> = self
+            .query(
+                &deps,
+                QueryMsg::NftInfo {
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(info.extension, self.extension(1));
+    }
+
+    pub fn check_send(&self) {
+        let mut deps = self.init();
+        let receiver = mock_env().contract.address;
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+
+        let payload = to_binary("hello").unwrap();
+        let res = self
+            .execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::SendNft {
+                    contract: receiver.to_string(),
+                    token_id: "1".to_string(),
+                    msg: payload.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "send_nft"),
+                attr("sender", ADDR1),
+                attr("recipient", receiver.as_str()),
+                attr("token_id", "1"),
+            ]
+        );
+
+        let expected = Cw721ReceiveMsg {
+            sender: ADDR1.to_string(),
+            token_id: "1".to_string(),
+            msg: payload,
+        };
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: receiver.to_string(),
+                msg: expected.into_binary().unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(self.owner_of(&deps, "1").owner, receiver.as_str());
+    }
+
+    pub fn check_burn(&self) {
+        let mut deps = self.init();
+        self.mint(&mut deps, ADDR1, "1").unwrap();
+        self.mint(&mut deps, ADDR1, "2").unwrap();
+
+        let burn = ExecuteMsg::Burn {
+            token_id: "1".to_string(),
+        };
+        let unauthorized = self.execute(&mut deps, ADDR2, burn.clone()).unwrap_err();
+        assert_eq!(unauthorized.to_string(), "Unauthorized");
+
+        let res = self.execute(&mut deps, ADDR1, burn.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            [
+                attr("action", "burn"),
+                attr("sender", ADDR1),
+                attr("token_id", "1"),
+            ]
+        );
+        assert_eq!(self.num_tokens(&deps), 1);
+
+        // cannot burn same nft again, and it is gone from every query
+        self.execute(&mut deps, ADDR1, burn).unwrap_err();
+        self.query::<NftInfoResponse<T>>(
+            &deps,
+            QueryMsg::NftInfo {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        let tokens: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllTokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(tokens.tokens, vec!["2".to_string()]);
+    }
+
+    pub fn check_pagination(&self) {
+        let mut deps = self.init();
+        // ids sort lexicographically, token_1 .. token_9 keep their numeric order
+        let ids: Vec<String> = (1..=9).map(|i| format!("token_{}", i)).collect();
+        for (index, token_id) in ids.iter().enumerate() {
+            let owner = if index % 3 == 0 { ADDR2 } else { ADDR1 };
+            self.mint(&mut deps, owner, token_id).unwrap();
+        }
+
+        let page: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllTokens {
+                    start_after: None,
+                    limit: Some(4),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.tokens, ids[..4]);
+        let page: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllTokens {
+                    start_after: page.tokens.last().cloned(),
+                    limit: Some(4),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.tokens, ids[4..8]);
+        let page: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllTokens {
+                    start_after: page.tokens.last().cloned(),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(page.tokens, ids[8..]);
+
+        let owned_by_addr2: Vec<String> = ids.iter().step_by(3).cloned().collect();
+        let page: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::Tokens {
+                    owner: ADDR2.to_string(),
+                    start_after: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.tokens, owned_by_addr2[..2]);
+        let page: TokensResponse = self
+            .query(
+                &deps,
+                QueryMsg::Tokens {
+                    owner: ADDR2.to_string(),
+                    start_after: page.tokens.last().cloned(),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.tokens, owned_by_addr2[2..]);
+
+        for operator in [ADDR2, ADDR3] {
+            self.execute(
+                &mut deps,
+                ADDR1,
+                ExecuteMsg::ApproveAll {
+                    operator: operator.to_string(),
+                    expires: None,
+                },
+            )
+            .unwrap();
+        }
+        let mut operators = [ADDR2, ADDR3];
+        operators.sort_unstable();
+        let page: OperatorsResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllOperators {
+                    owner: ADDR1.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.operators.len(), 1);
+        assert_eq!(page.operators[0].spender, operators[0]);
+        let page: OperatorsResponse = self
+            .query(
+                &deps,
+                QueryMsg::AllOperators {
+                    owner: ADDR1.to_string(),
+                    include_expired: None,
+                    start_after: Some(operators[0].to_string()),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(page.operators.len(), 1);
+        assert_eq!(page.operators[0].spender, operators[1]);
+    }
+}
@@ -0,0 +1,185 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, Ident, Path, Result, Token, Type};
+
+/// `key: Type` or `key: Type => handler`
+struct Entry {
+    key: Ident,
+    ty: Type,
+    handler: Option<Path>,
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        let handler = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Entry { key, ty, handler })
+    }
+}
+
+pub struct EntryPoints {
+    extension: Type,
+    execute: Option<(Type, Path)>,
+    query: Option<(Type, Path)>,
+    error: Option<Type>,
+}
+
+impl Parse for EntryPoints {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let entries = input.parse_terminated::<Entry, Token![,]>(Entry::parse)?;
+
+        let mut extension = None;
+        let mut execute = None;
+        let mut query = None;
+        let mut error = None;
+        for entry in entries {
+            match (entry.key.to_string().as_str(), entry.handler) {
+                ("extension", None) => extension = Some(entry.ty),
+                ("error", None) => error = Some(entry.ty),
+                ("execute", Some(handler)) => execute = Some((entry.ty, handler)),
+                ("query", Some(handler)) => query = Some((entry.ty, handler)),
+                ("execute", None) | ("query", None) => {
+                    return Err(Error::new_spanned(
+                        &entry.key,
+                        "expected `Msg => handler` for the custom messages",
+                    ))
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        &entry.key,
+                        "expected one of `extension`, `execute`, `query`, `error`",
+                    ))
+                }
+            }
+        }
+
+        let extension =
+            extension.ok_or_else(|| input.error("missing `extension: YourCw721Extension`"))?;
+        Ok(EntryPoints {
+            extension,
+            execute,
+            query,
+            error,
+        })
+    }
+}
+
+impl EntryPoints {
+    pub fn expand(self) -> TokenStream {
+        let ext = &self.extension;
+        let ext_trait = quote! { <#ext as ::cw721_simple_base::extension::Cw721Extension> };
+        let response = quote! { ::cosmwasm_std::Response<#ext_trait::CustomMsg> };
+        let error = match &self.error {
+            Some(error) => quote! { #error },
+            None => quote! { ::cw721_simple_base::error::ContractError<#ext_trait::Error> },
+        };
+        let base_execute_msg = quote! { ::cw721_simple_base::msg::ExecuteMsg<#ext_trait::Extension, #ext_trait::ExecuteMsg> };
+        let base_query_msg = quote! { ::cw721_simple_base::msg::QueryMsg<#ext_trait::QueryMsg> };
+        let extension = quote! { &<#ext as ::std::default::Default>::default() };
+
+        let execute = match &self.execute {
+            Some((msg, handler)) => quote! {
+                #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+                pub fn execute(
+                    deps: ::cosmwasm_std::DepsMut,
+                    env: ::cosmwasm_std::Env,
+                    info: ::cosmwasm_std::MessageInfo,
+                    msg: #msg,
+                ) -> ::std::result::Result<#response, #error> {
+                    match <#base_execute_msg as ::std::convert::TryFrom<#msg>>::try_from(msg) {
+                        Ok(msg) => ::cw721_simple_base::contract::execute(
+                            deps, env, info, msg, #extension,
+                        )
+                        .map_err(::std::convert::Into::into),
+                        Err(msg) => #handler(deps, env, info, msg),
+                    }
+                }
+            },
+            None => quote! {
+                #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+                pub fn execute(
+                    deps: ::cosmwasm_std::DepsMut,
+                    env: ::cosmwasm_std::Env,
+                    info: ::cosmwasm_std::MessageInfo,
+                    msg: #base_execute_msg,
+                ) -> ::std::result::Result<#response, #error> {
+                    ::cw721_simple_base::contract::execute(deps, env, info, msg, #extension)
+                        .map_err(::std::convert::Into::into)
+                }
+            },
+        };
+
+        let query = match &self.query {
+            Some((msg, handler)) => quote! {
+                #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+                pub fn query(
+                    deps: ::cosmwasm_std::Deps,
+                    env: ::cosmwasm_std::Env,
+                    msg: #msg,
+                ) -> ::cosmwasm_std::StdResult<::cosmwasm_std::Binary> {
+                    match <#base_query_msg as ::std::convert::TryFrom<#msg>>::try_from(msg) {
+                        Ok(msg) => ::cw721_simple_base::contract::query(deps, env, msg, #extension),
+                        Err(msg) => #handler(deps, env, msg),
+                    }
+                }
+            },
+            None => quote! {
+                #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+                pub fn query(
+                    deps: ::cosmwasm_std::Deps,
+                    env: ::cosmwasm_std::Env,
+                    msg: #base_query_msg,
+                ) -> ::cosmwasm_std::StdResult<::cosmwasm_std::Binary> {
+                    ::cw721_simple_base::contract::query(deps, env, msg, #extension)
+                }
+            },
+        };
+
+        quote! {
+            #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+            pub fn instantiate(
+                deps: ::cosmwasm_std::DepsMut,
+                env: ::cosmwasm_std::Env,
+                info: ::cosmwasm_std::MessageInfo,
+                msg: ::cw721_simple_base::msg::InstantiateMsg<#ext_trait::Extension>,
+            ) -> ::std::result::Result<#response, #error> {
+                ::cw721_simple_base::contract::instantiate::<
+                    #ext_trait::Extension,
+                    #ext_trait::CustomMsg,
+                    #ext_trait::Error,
+                >(
+                    deps, env, info, msg,
+                )
+                .map_err(::std::convert::Into::into)
+            }
+
+            #execute
+
+            #query
+
+            #[cfg_attr(not(feature = "library"), ::cosmwasm_std::entry_point)]
+            pub fn migrate(
+                deps: ::cosmwasm_std::DepsMut,
+                env: ::cosmwasm_std::Env,
+                msg: ::cw721_simple_base::msg::MigrateMsg,
+            ) -> ::std::result::Result<#response, #error> {
+                ::cw721_simple_base::contract::migrate::<
+                    #ext_trait::Extension,
+                    #ext_trait::CustomMsg,
+                    #ext_trait::Error,
+                >(
+                    deps, env, msg,
+                )
+                .map_err(::std::convert::Into::into)
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Error, Fields, Ident, Lit, LitStr, Meta, MetaNameValue, Result, Type,
+    Variant, WherePredicate,
+};
+
+/// implements From<cw721_simple_base::error::ContractError<E>> for the enum,
+/// wrapping the base error into the variant marked with #[cw721]
+pub fn expand_cw721_error(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Cw721Error can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut marked = data
+        .variants
+        .iter()
+        .filter(|v| v.attrs.iter().any(|attr| attr.path.is_ident("cw721")));
+    let variant = marked.next().ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "mark the variant wrapping the cw721 ContractError with #[cw721]",
+        )
+    })?;
+    if let Some(duplicate) = marked.next() {
+        return Err(Error::new_spanned(
+            &duplicate.ident,
+            "only one variant can be marked with #[cw721]",
+        ));
+    }
+
+    let field = match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+        _ => {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                "#[cw721] variant must wrap exactly one unnamed ContractError field",
+            ))
+        }
+    };
+
+    let variant_name = &variant.ident;
+    let wrapped = &field.ty;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::convert::From<#wrapped> for #name #ty_generics #where_clause {
+            fn from(err: #wrapped) -> Self {
+                #name::#variant_name(err)
+            }
+        }
+    })
+}
+
+/// implements code() from the `#[code = "..."]` of every variant, and map_custom() converting
+/// the variant that wraps the custom error, the only type parameter of the enum
+pub fn expand_error_code(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ErrorCode can only be derived for enums",
+            ))
+        }
+    };
+    let params: Vec<_> = input.generics.type_params().collect();
+    let param = match params.as_slice() {
+        [param] => param,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.generics,
+                "ErrorCode needs exactly one type parameter, the custom error",
+            ))
+        }
+    };
+    let custom = &param.ident;
+
+    let mut code_arms = Vec::with_capacity(data.variants.len());
+    let mut map_arms = Vec::with_capacity(data.variants.len());
+    let mut custom_variants = 0;
+    for variant in &data.variants {
+        let code = variant_code(variant)?;
+        let ident = &variant.ident;
+        code_arms.push(quote! { #name::#ident { .. } => #code, });
+        map_arms.push(match &variant.fields {
+            Fields::Named(fields) => {
+                let fields: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                quote! { #name::#ident { #(#fields),* } => #name::#ident { #(#fields),* }, }
+            }
+            Fields::Unnamed(fields)
+                if fields.unnamed.len() == 1 && is_param(&fields.unnamed[0].ty, custom) =>
+            {
+                custom_variants += 1;
+                quote! { #name::#ident(err) => #name::#ident(op(err)), }
+            }
+            Fields::Unnamed(fields) => {
+                let fields: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| format_ident!("field{}", i))
+                    .collect();
+                quote! { #name::#ident(#(#fields),*) => #name::#ident(#(#fields),*), }
+            }
+            Fields::Unit => quote! { #name::#ident => #name::#ident, },
+        });
+    }
+    if custom_variants != 1 {
+        return Err(Error::new_spanned(
+            &input.ident,
+            format!(
+                "exactly one variant must wrap the custom error `{}`",
+                custom
+            ),
+        ));
+    }
+
+    // the new custom error needs the bounds of the current one
+    let mut bounds: Vec<_> = param.bounds.iter().collect();
+    if let Some(where_clause) = &input.generics.where_clause {
+        for predicate in &where_clause.predicates {
+            if let WherePredicate::Type(predicate) = predicate {
+                if is_param(&predicate.bounded_ty, custom) {
+                    bounds.extend(predicate.bounds.iter());
+                }
+            }
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Stable machine-readable code of the error, it doesn't change with the message
+            pub fn code(&self) -> &'static str {
+                match self {
+                    #(#code_arms)*
+                }
+            }
+
+            /// Converts the custom error, so errors of extensions with different custom errors compose
+            pub fn map_custom<F, O>(self, op: O) -> #name<F>
+            where
+                F: #(#bounds)+*,
+                O: ::std::ops::FnOnce(#custom) -> F,
+            {
+                match self {
+                    #(#map_arms)*
+                }
+            }
+        }
+    })
+}
+
+fn variant_code(variant: &Variant) -> Result<LitStr> {
+    let attr = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("code"))
+        .ok_or_else(|| {
+            Error::new_spanned(&variant.ident, "every variant needs a `#[code = \"...\"]`")
+        })?;
+    match attr.parse_meta()? {
+        Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(code),
+            ..
+        }) => Ok(code),
+        _ => Err(Error::new_spanned(attr, "expected `#[code = \"...\"]`")),
+    }
+}
+
+fn is_param(ty: &Type, param: &Ident) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident(param))
+}
@@ -0,0 +1,78 @@
+//! Macros generating the boilerplate of contracts built on cw721-simple-base
+//!
+//! * `cw721_entry_points!` - instantiate, execute, query and migrate entry points forwarding to cw721-simple-base
+//! * `#[cw721_execute(Extension)]`, `#[cw721_query(Extension)]` - merge the cw721-simple-base messages and your own in one enum
+//! * `#[derive(Cw721Error)]` - `From` conversion of the base ContractError into your own error
+//! * `#[derive(ErrorCode)]` - `code()` and `map_custom()` of the base ContractError
+
+mod entry;
+mod error;
+mod msg;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemEnum, Type};
+
+/// Generates `instantiate`, `execute`, `query` and `migrate` entry points for a type implementing
+/// `cw721_simple_base::extension::Cw721Extension` + `Default`.
+///
+/// ```ignore
+/// cw721_entry_points! {
+///     extension: MetadataContract,
+///     // optional, enums flattened with #[cw721_execute] / #[cw721_query]
+///     // whose Custom<Enum> messages are passed to the handler
+///     execute: ExecuteMsg => execute_custom,
+///     query: QueryMsg => query_custom,
+///     // optional, defaults to cw721_simple_base::error::ContractError<Extension::Error>
+///     error: ContractError,
+/// }
+/// ```
+#[proc_macro]
+pub fn cw721_entry_points(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as entry::EntryPoints)
+        .expand()
+        .into()
+}
+
+/// Wraps `cw721_simple_base::msg::ExecuteMsg` and the variants of the enum, moved into
+/// `Custom<Enum>`, in an untagged enum accepting the JSON of both.
+/// The argument is the `Cw721Extension` of the contract, `#[cw721_execute(MetadataContract)]`,
+/// and `TryFrom<Enum>` returns the base message or the custom one as error.
+/// Base variants take precedence over custom variants of the same name
+#[proc_macro_attribute]
+pub fn cw721_execute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let extension = parse_macro_input!(attr as Type);
+    let item = parse_macro_input!(item as ItemEnum);
+    msg::expand_execute(extension, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Same as `#[cw721_execute]` for `cw721_simple_base::msg::QueryMsg`, `#[cw721_query(MetadataContract)]`
+#[proc_macro_attribute]
+pub fn cw721_query(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let extension = parse_macro_input!(attr as Type);
+    let item = parse_macro_input!(item as ItemEnum);
+    msg::expand_query(extension, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `From<cw721_simple_base::error::ContractError<E>>` for an error enum,
+/// wrapping it in the variant marked with `#[cw721]`
+#[proc_macro_derive(Cw721Error, attributes(cw721))]
+pub fn derive_cw721_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    error::expand_cw721_error(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `code()` returning the `#[code = "..."]` of the variant, and `map_custom()`
+/// converting the variant that wraps the custom error, the only type parameter of the enum
+#[proc_macro_derive(ErrorCode, attributes(code))]
+pub fn derive_error_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    error::expand_error_code(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,85 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, ItemEnum, Result, Type};
+
+/// moves the variants of the user enum into `Custom<Enum>` and turns the enum into an untagged
+/// wrapper of the cw721-simple-base message and the custom one, so both keep their flat JSON
+fn wrap(item: ItemEnum, base_msg: TokenStream) -> Result<TokenStream> {
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "cw721 message enums can't be generic",
+        ));
+    }
+
+    let name = &item.ident;
+    let vis = &item.vis;
+    let custom_name = format_ident!("Custom{}", name);
+    let (docs, attrs): (Vec<Attribute>, Vec<Attribute>) = item
+        .attrs
+        .iter()
+        .cloned()
+        .partition(|attr| attr.path.is_ident("doc"));
+
+    let mut custom = item.clone();
+    custom.ident = custom_name.clone();
+    custom.attrs = attrs.clone();
+    let custom_doc = format!(
+        "Custom variants of [`{}`], passed to the custom handler",
+        name
+    );
+
+    Ok(quote! {
+        #(#docs)*
+        #(#attrs)*
+        #[serde(untagged)]
+        // the base message carries the metadata extension by value
+        #[allow(clippy::large_enum_variant)]
+        #vis enum #name {
+            /// Message handled by cw721-simple-base
+            Cw721(#base_msg),
+            /// Message declared by the contract
+            Custom(#custom_name),
+        }
+
+        #[doc = #custom_doc]
+        #custom
+
+        impl ::std::convert::From<#base_msg> for #name {
+            fn from(msg: #base_msg) -> Self {
+                #name::Cw721(msg)
+            }
+        }
+
+        impl ::std::convert::From<#custom_name> for #name {
+            fn from(msg: #custom_name) -> Self {
+                #name::Custom(msg)
+            }
+        }
+
+        impl ::std::convert::TryFrom<#name> for #base_msg {
+            type Error = #custom_name;
+
+            fn try_from(msg: #name) -> ::std::result::Result<Self, #custom_name> {
+                match msg {
+                    #name::Cw721(msg) => Ok(msg),
+                    #name::Custom(msg) => Err(msg),
+                }
+            }
+        }
+    })
+}
+
+pub fn expand_execute(extension: Type, item: ItemEnum) -> Result<TokenStream> {
+    let ext_trait = quote! { <#extension as ::cw721_simple_base::extension::Cw721Extension> };
+    let base_msg = quote! {
+        ::cw721_simple_base::msg::ExecuteMsg<#ext_trait::Extension, #ext_trait::ExecuteMsg>
+    };
+    wrap(item, base_msg)
+}
+
+pub fn expand_query(extension: Type, item: ItemEnum) -> Result<TokenStream> {
+    let ext_trait = quote! { <#extension as ::cw721_simple_base::extension::Cw721Extension> };
+    let base_msg = quote! { ::cw721_simple_base::msg::QueryMsg<#ext_trait::QueryMsg> };
+    wrap(item, base_msg)
+}
@@ -0,0 +1,550 @@
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::{execute, query};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+use cw721::CustomMsg;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const CONTRACT_NAME: &str = "crates.io:cw721-simple-multi-collection";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Collections are created afterwards with ExecuteMsg::CreateCollection
+pub fn instantiate<C>(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+pub fn execute<T, C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg<T>,
+) -> Result<Response<C>, ContractError>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+{
+    match msg {
+        ExecuteMsg::CreateCollection {
+            collection_id,
+            name,
+            symbol,
+            minter,
+        } => execute::create_collection(deps, env, info, collection_id, name, symbol, minter),
+        ExecuteMsg::Mint {
+            collection_id,
+            token_id,
+            owner,
+            token_uri,
+            extension,
+        } => execute::mint(
+            deps,
+            env,
+            info,
+            collection_id,
+            token_id,
+            owner,
+            token_uri,
+            extension,
+        ),
+        ExecuteMsg::Approve {
+            collection_id,
+            spender,
+            token_id,
+            expires,
+        } => execute::approve(deps, env, info, collection_id, spender, token_id, expires),
+        ExecuteMsg::Revoke {
+            collection_id,
+            spender,
+            token_id,
+        } => execute::revoke(deps, env, info, collection_id, spender, token_id),
+        ExecuteMsg::ApproveAll {
+            collection_id,
+            operator,
+            expires,
+        } => execute::approve_all(deps, env, info, collection_id, operator, expires),
+        ExecuteMsg::RevokeAll {
+            collection_id,
+            operator,
+        } => execute::revoke_all(deps, env, info, collection_id, operator),
+        ExecuteMsg::TransferNft {
+            collection_id,
+            recipient,
+            token_id,
+        } => execute::transfer_nft(deps, env, info, collection_id, recipient, token_id),
+        ExecuteMsg::SendNft {
+            collection_id,
+            contract,
+            token_id,
+            msg,
+        } => execute::send_nft(deps, env, info, collection_id, contract, token_id, msg),
+        ExecuteMsg::Burn {
+            collection_id,
+            token_id,
+        } => execute::burn::<T, C>(deps, env, info, collection_id, token_id),
+    }
+}
+
+pub fn query<T>(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    match msg {
+        QueryMsg::Collections { start_after, limit } => {
+            query::collections(deps, start_after, limit)
+        }
+        QueryMsg::Minter { collection_id } => query::minter(deps, collection_id),
+        QueryMsg::ContractInfo { collection_id } => query::contract_info(deps, collection_id),
+        QueryMsg::NftInfo {
+            collection_id,
+            token_id,
+        } => query::nft_info::<T>(deps, collection_id, token_id),
+        QueryMsg::OwnerOf {
+            collection_id,
+            token_id,
+            include_expired,
+        } => query::owner_of(
+            deps,
+            env,
+            collection_id,
+            token_id,
+            include_expired.unwrap_or(false),
+        ),
+        QueryMsg::AllNftInfo {
+            collection_id,
+            token_id,
+            include_expired,
+        } => query::all_nft_info::<T>(
+            deps,
+            env,
+            collection_id,
+            token_id,
+            include_expired.unwrap_or(false),
+        ),
+        QueryMsg::AllOperators {
+            collection_id,
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => query::operators(
+            deps,
+            env,
+            collection_id,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        ),
+        QueryMsg::NumTokens { collection_id } => query::num_tokens(deps, collection_id),
+        QueryMsg::AllTokens {
+            collection_id,
+            start_after,
+            limit,
+        } => query::all_tokens(deps, collection_id, start_after, limit),
+        QueryMsg::Approval {
+            collection_id,
+            token_id,
+            spender,
+            include_expired,
+        } => query::approval(
+            deps,
+            env,
+            collection_id,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        ),
+        QueryMsg::Approvals {
+            collection_id,
+            token_id,
+            include_expired,
+        } => query::approvals(
+            deps,
+            env,
+            collection_id,
+            token_id,
+            include_expired.unwrap_or(false),
+        ),
+        QueryMsg::Tokens {
+            collection_id,
+            owner,
+            start_after,
+            limit,
+        } => query::tokens(deps, collection_id, owner, start_after, limit),
+    }
+}
+
+#[cfg(test)]
+pub mod contract_tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::msg::{
+        CollectionReceiveMsg, CollectionResponse, CollectionsResponse, ExecuteMsg, InstantiateMsg,
+        QueryMsg, ReceiverExecuteMsg,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        from_binary, to_binary, CosmosMsg, Deps, DepsMut, Empty, Response, StdError, WasmMsg,
+    };
+    use cw721::{
+        Expiration, NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+    };
+
+    const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+    const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+    const ADDR3: &str = "cosmos18zfp9u7zxg3gel4r3txa2jqxme7jkw7dka7zjv";
+
+    type Extension = Option<Empty>;
+
+    fn exec(
+        deps: DepsMut,
+        sender: &str,
+        msg: ExecuteMsg<Extension>,
+    ) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn create(deps: DepsMut, sender: &str, collection_id: &str) -> Result<Response, ContractError> {
+        exec(
+            deps,
+            sender,
+            ExecuteMsg::CreateCollection {
+                collection_id: collection_id.to_string(),
+                name: format!("{} name", collection_id),
+                symbol: collection_id.to_uppercase(),
+                minter: None,
+            },
+        )
+    }
+
+    fn mint(
+        deps: DepsMut,
+        sender: &str,
+        collection_id: &str,
+        token_id: &str,
+        owner: &str,
+    ) -> Result<Response, ContractError> {
+        exec(
+            deps,
+            sender,
+            ExecuteMsg::Mint {
+                collection_id: collection_id.to_string(),
+                token_id: token_id.to_string(),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+        )
+    }
+
+    fn transfer(
+        deps: DepsMut,
+        sender: &str,
+        collection_id: &str,
+        token_id: &str,
+        recipient: &str,
+    ) -> Result<Response, ContractError> {
+        exec(
+            deps,
+            sender,
+            ExecuteMsg::TransferNft {
+                collection_id: collection_id.to_string(),
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            },
+        )
+    }
+
+    fn owner_of(deps: Deps, collection_id: &str, token_id: &str) -> Result<String, StdError> {
+        let msg = QueryMsg::OwnerOf {
+            collection_id: collection_id.to_string(),
+            token_id: token_id.to_string(),
+            include_expired: None,
+        };
+        let res: OwnerOfResponse = from_binary(&query::<Extension>(deps, mock_env(), msg)?)?;
+        Ok(res.owner)
+    }
+
+    fn tokens(
+        deps: Deps,
+        collection_id: &str,
+        owner: &str,
+        start_after: Option<&str>,
+    ) -> Vec<String> {
+        let msg = QueryMsg::Tokens {
+            collection_id: collection_id.to_string(),
+            owner: owner.to_string(),
+            start_after: start_after.map(String::from),
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query::<Extension>(deps, mock_env(), msg).unwrap()).unwrap();
+        res.tokens
+    }
+
+    fn all_tokens(deps: Deps, collection_id: &str, start_after: Option<&str>) -> Vec<String> {
+        let msg = QueryMsg::AllTokens {
+            collection_id: collection_id.to_string(),
+            start_after: start_after.map(String::from),
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_binary(&query::<Extension>(deps, mock_env(), msg).unwrap()).unwrap();
+        res.tokens
+    }
+
+    #[test]
+    fn test_create_collection() {
+        let mut deps = mock_dependencies();
+        instantiate::<Empty>(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADDR1, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        create(deps.as_mut(), ADDR1, "apes").unwrap();
+        exec(
+            deps.as_mut(),
+            ADDR2,
+            ExecuteMsg::CreateCollection {
+                collection_id: "birds".to_string(),
+                name: "birds name".to_string(),
+                symbol: "BIRDS".to_string(),
+                minter: Some(ADDR3.to_string()),
+            },
+        )
+        .unwrap();
+
+        // ids are claimed by the first creator
+        let err = create(deps.as_mut(), ADDR2, "apes").unwrap_err();
+        assert_eq!(err, ContractError::CollectionClaimed {});
+
+        mint(deps.as_mut(), ADDR1, "apes", "1", ADDR2).unwrap();
+
+        let res: CollectionsResponse = from_binary(
+            &query::<Extension>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Collections {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.collections,
+            vec![
+                CollectionResponse {
+                    collection_id: "apes".to_string(),
+                    name: "apes name".to_string(),
+                    symbol: "APES".to_string(),
+                    minter: ADDR1.to_string(),
+                    num_tokens: 1,
+                },
+                CollectionResponse {
+                    collection_id: "birds".to_string(),
+                    name: "birds name".to_string(),
+                    symbol: "BIRDS".to_string(),
+                    minter: ADDR3.to_string(),
+                    num_tokens: 0,
+                },
+            ]
+        );
+
+        let res: CollectionsResponse = from_binary(
+            &query::<Extension>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Collections {
+                    start_after: Some("apes".to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.collections.len(), 1);
+        assert_eq!(res.collections[0].collection_id, "birds");
+
+        // unknown collections are rejected
+        let err = mint(deps.as_mut(), ADDR1, "cats", "1", ADDR2).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CollectionNotFound {
+                collection_id: "cats".to_string()
+            }
+        );
+        let err = query::<Extension>(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NumTokens {
+                collection_id: "cats".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("collection_not_found: Collection not found: cats")
+        );
+    }
+
+    #[test]
+    fn test_collections_are_isolated() {
+        let mut deps = mock_dependencies();
+        create(deps.as_mut(), ADDR1, "apes").unwrap();
+        create(deps.as_mut(), ADDR2, "birds").unwrap();
+
+        // the minter of one collection cannot mint in another
+        let err = mint(deps.as_mut(), ADDR1, "birds", "1", ADDR1).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // the same token id lives in both collections
+        mint(deps.as_mut(), ADDR1, "apes", "1", ADDR1).unwrap();
+        mint(deps.as_mut(), ADDR1, "apes", "2", ADDR1).unwrap();
+        mint(deps.as_mut(), ADDR2, "birds", "1", ADDR2).unwrap();
+        let err = mint(deps.as_mut(), ADDR1, "apes", "1", ADDR1).unwrap_err();
+        assert_eq!(err, ContractError::Claimed {});
+
+        assert_eq!(owner_of(deps.as_ref(), "apes", "1").unwrap(), ADDR1);
+        assert_eq!(owner_of(deps.as_ref(), "birds", "1").unwrap(), ADDR2);
+        let count: NumTokensResponse = from_binary(
+            &query::<Extension>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NumTokens {
+                    collection_id: "birds".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(count.count, 1);
+
+        // operators are granted for one collection only
+        exec(
+            deps.as_mut(),
+            ADDR2,
+            ExecuteMsg::ApproveAll {
+                collection_id: "birds".to_string(),
+                operator: ADDR1.to_string(),
+                expires: None,
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), ADDR1, "apes", "3", ADDR2).unwrap();
+        let err = transfer(deps.as_mut(), ADDR1, "apes", "3", ADDR1).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        transfer(deps.as_mut(), ADDR1, "birds", "1", ADDR3).unwrap();
+        assert_eq!(owner_of(deps.as_ref(), "birds", "1").unwrap(), ADDR3);
+        assert_eq!(owner_of(deps.as_ref(), "apes", "1").unwrap(), ADDR1);
+
+        let res: OperatorsResponse = from_binary(
+            &query::<Extension>(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllOperators {
+                    collection_id: "apes".to_string(),
+                    owner: ADDR2.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(res.operators.is_empty());
+
+        // enumeration stays inside the collection
+        assert_eq!(tokens(deps.as_ref(), "apes", ADDR1, None), vec!["1", "2"]);
+        assert_eq!(tokens(deps.as_ref(), "apes", ADDR1, Some("1")), vec!["2"]);
+        assert_eq!(
+            tokens(deps.as_ref(), "birds", ADDR1, None),
+            Vec::<String>::new()
+        );
+        assert_eq!(tokens(deps.as_ref(), "birds", ADDR3, None), vec!["1"]);
+        assert_eq!(all_tokens(deps.as_ref(), "apes", None), vec!["1", "2", "3"]);
+        assert_eq!(all_tokens(deps.as_ref(), "apes", Some("2")), vec!["3"]);
+        assert_eq!(all_tokens(deps.as_ref(), "birds", None), vec!["1"]);
+
+        // burning only touches its own collection
+        exec(
+            deps.as_mut(),
+            ADDR1,
+            ExecuteMsg::Burn {
+                collection_id: "apes".to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            owner_of(deps.as_ref(), "apes", "1").unwrap_err(),
+            StdError::not_found("cw721_simple_multi_collection::state::TokenInfo")
+        );
+        assert_eq!(owner_of(deps.as_ref(), "birds", "1").unwrap(), ADDR3);
+    }
+
+    #[test]
+    fn test_send_nft() {
+        let mut deps = mock_dependencies();
+        create(deps.as_mut(), ADDR1, "apes").unwrap();
+        mint(deps.as_mut(), ADDR1, "apes", "1", ADDR1).unwrap();
+        exec(
+            deps.as_mut(),
+            ADDR1,
+            ExecuteMsg::Approve {
+                collection_id: "apes".to_string(),
+                spender: ADDR2.to_string(),
+                token_id: "1".to_string(),
+                expires: Some(Expiration::Never {}),
+            },
+        )
+        .unwrap();
+
+        let msg = to_binary("payload").unwrap();
+        let res = exec(
+            deps.as_mut(),
+            ADDR2,
+            ExecuteMsg::SendNft {
+                collection_id: "apes".to_string(),
+                contract: ADDR3.to_string(),
+                token_id: "1".to_string(),
+                msg: msg.clone(),
+            },
+        )
+        .unwrap();
+
+        // the receiver is told which collection the token comes from
+        let expected = ReceiverExecuteMsg::ReceiveCollectionNft(CollectionReceiveMsg {
+            collection_id: "apes".to_string(),
+            sender: ADDR2.to_string(),
+            token_id: "1".to_string(),
+            msg,
+        });
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ADDR3.to_string(),
+                msg: to_binary(&expected).unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(owner_of(deps.as_ref(), "apes", "1").unwrap(), ADDR3);
+    }
+}
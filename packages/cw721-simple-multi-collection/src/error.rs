@@ -0,0 +1,60 @@
+use crate::state::TokenInfo;
+use cosmwasm_std::StdError;
+use std::any::type_name;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("token_id already claimed")]
+    Claimed {},
+
+    #[error("Cannot set approval that is already expired")]
+    Expired {},
+
+    #[error("Token not found: {token_id}")]
+    TokenNotFound { token_id: String },
+
+    #[error("Approval not found for: {spender}")]
+    ApprovalNotFound { spender: String },
+
+    #[error("Collection not found: {collection_id}")]
+    CollectionNotFound { collection_id: String },
+
+    #[error("collection_id already claimed")]
+    CollectionClaimed {},
+}
+
+impl ContractError {
+    /// Stable machine-readable code of the error, same codes as cw721-simple-base
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::Std(_) => "std",
+            ContractError::Unauthorized {} => "unauthorized",
+            ContractError::Claimed {} => "claimed",
+            ContractError::Expired {} => "expired",
+            ContractError::TokenNotFound { .. } => "token_not_found",
+            ContractError::ApprovalNotFound { .. } => "approval_not_found",
+            ContractError::CollectionNotFound { .. } => "collection_not_found",
+            ContractError::CollectionClaimed {} => "collection_claimed",
+        }
+    }
+}
+
+/// Queries answer with StdError. Missing tokens and approvals stay NotFound as in cw721-base,
+/// the other contract errors are kept as generic errors carrying their code
+impl From<ContractError> for StdError {
+    fn from(err: ContractError) -> Self {
+        match err {
+            ContractError::Std(err) => err,
+            ContractError::TokenNotFound { .. } => StdError::not_found(type_name::<TokenInfo>()),
+            ContractError::ApprovalNotFound { .. } => StdError::not_found("Approval not found"),
+            err => StdError::generic_err(format!("{}: {}", err.code(), err)),
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use crate::error::ContractError;
+use crate::msg::{CollectionReceiveMsg, ReceiverExecuteMsg};
+use crate::state::{
+    decrement_tokens, get_token_metadata, get_tokens, increment_tokens, load_minter, load_token,
+    TokenInfo, CONTRACT_INFO, MINTER, OPERATORS, TOKENS_COUNT,
+};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, WasmMsg};
+use cw721::{ContractInfoResponse, CustomMsg, Expiration};
+use cw721_simple_base::state::{Approval, TokenMetadata};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub fn create_collection<C>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    name: String,
+    symbol: String,
+    minter: Option<String>,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    let minter = match minter {
+        Some(minter) => deps.api.addr_validate(&minter)?,
+        None => info.sender.clone(),
+    };
+
+    CONTRACT_INFO.update(deps.storage, &collection_id, |old| match old {
+        Some(_) => Err(ContractError::CollectionClaimed {}),
+        None => Ok(ContractInfoResponse { name, symbol }),
+    })?;
+    MINTER.save(deps.storage, &collection_id, &minter)?;
+    TOKENS_COUNT.save(deps.storage, &collection_id, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_collection")
+        .add_attribute("sender", info.sender)
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("minter", minter))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mint<T, C>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    token_id: String,
+    owner: String,
+    token_uri: Option<String>,
+    extension: T,
+) -> Result<Response<C>, ContractError>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+{
+    let minter = load_minter(deps.storage, &collection_id)?;
+
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // create the token
+    let token = TokenInfo {
+        collection_id: collection_id.clone(),
+        owner: deps.api.addr_validate(&owner)?,
+        approvals: vec![],
+    };
+    let metadata = TokenMetadata {
+        token_uri,
+        extension,
+    };
+
+    let key = (collection_id.as_str(), token_id.as_str());
+    get_tokens().update(deps.storage, key, |old| match old {
+        Some(_) => Err(ContractError::Claimed {}),
+        None => Ok(token),
+    })?;
+    get_token_metadata().save(deps.storage, key, &metadata)?;
+
+    increment_tokens(deps.storage, &collection_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("minter", info.sender)
+        .add_attribute("owner", owner)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    _update_approvals(
+        deps,
+        &env,
+        &info,
+        &collection_id,
+        &spender,
+        &token_id,
+        true,
+        expires,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn revoke<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    spender: String,
+    token_id: String,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    _update_approvals(
+        deps,
+        &env,
+        &info,
+        &collection_id,
+        &spender,
+        &token_id,
+        false,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve_all<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    load_minter(deps.storage, &collection_id)?;
+
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    // set the operator for us, on this collection only
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.save(
+        deps.storage,
+        (&collection_id, &info.sender, &operator_addr),
+        &expires,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_all")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn revoke_all<C>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    operator: String,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&collection_id, &info.sender, &operator_addr));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_all")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn burn<T, C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    token_id: String,
+) -> Result<Response<C>, ContractError>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+{
+    let token = load_token(deps.storage, &collection_id, &token_id)?;
+    check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+    let key = (collection_id.as_str(), token_id.as_str());
+    get_tokens().remove(deps.storage, key)?;
+    get_token_metadata::<T>().remove(deps.storage, key);
+    decrement_tokens(deps.storage, &collection_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn transfer_nft<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    recipient: String,
+    token_id: String,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    _transfer_nft(deps, &env, &info, &collection_id, &recipient, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn send_nft<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_id: String,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg,
+{
+    // Transfer token
+    _transfer_nft(deps, &env, &info, &collection_id, &contract, &token_id)?;
+
+    let send = ReceiverExecuteMsg::ReceiveCollectionNft(CollectionReceiveMsg {
+        collection_id: collection_id.clone(),
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    });
+
+    // Send message
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: contract.clone(),
+            msg: to_binary(&send)?,
+            funds: vec![],
+        })
+        .add_attribute("action", "send_nft")
+        .add_attribute("collection_id", collection_id)
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+fn _transfer_nft(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    collection_id: &str,
+    recipient: &str,
+    token_id: &str,
+) -> Result<TokenInfo, ContractError> {
+    let mut token = load_token(deps.storage, collection_id, token_id)?;
+    // ensure we have permissions
+    check_can_send(deps.as_ref(), env, info, &token)?;
+    // set owner and remove existing approvals
+    token.owner = deps.api.addr_validate(recipient)?;
+    token.approvals = vec![];
+    get_tokens().save(deps.storage, (collection_id, token_id), &token)?;
+    Ok(token)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _update_approvals(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    collection_id: &str,
+    spender: &str,
+    token_id: &str,
+    // if add == false, remove. if add == true, remove then set with this expiration
+    add: bool,
+    expires: Option<Expiration>,
+) -> Result<TokenInfo, ContractError> {
+    let mut token = load_token(deps.storage, collection_id, token_id)?;
+    // ensure we have permissions
+    check_can_approve(deps.as_ref(), env, info, &token)?;
+
+    // update the approval list (remove any for the same spender before adding)
+    let spender_addr = deps.api.addr_validate(spender)?;
+    token.approvals.retain(|apr| apr.spender != spender_addr);
+
+    // only difference between approve and revoke
+    if add {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        let approval = Approval {
+            spender: spender_addr,
+            expires,
+        };
+        token.approvals.push(approval);
+    }
+
+    get_tokens().save(deps.storage, (collection_id, token_id), &token)?;
+
+    Ok(token)
+}
+
+/// returns true iff the sender can execute approve or reject on the token
+pub fn check_can_approve(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token: &TokenInfo,
+) -> Result<(), ContractError> {
+    // owner can approve
+    if token.owner == info.sender {
+        return Ok(());
+    }
+    // operator of the token's collection can approve
+    check_operator(deps, env, info, token)
+}
+
+/// returns true iff the sender can transfer ownership of the token
+pub fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token: &TokenInfo,
+) -> Result<(), ContractError> {
+    // owner can send
+    if token.owner == info.sender {
+        return Ok(());
+    }
+
+    // any non-expired token approval can send
+    if token
+        .approvals
+        .iter()
+        .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+    {
+        return Ok(());
+    }
+
+    // operator of the token's collection can send
+    check_operator(deps, env, info, token)
+}
+
+fn check_operator(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token: &TokenInfo,
+) -> Result<(), ContractError> {
+    let op = OPERATORS.may_load(
+        deps.storage,
+        (&token.collection_id, &token.owner, &info.sender),
+    )?;
+    match op {
+        Some(ex) => {
+            if ex.is_expired(&env.block) {
+                Err(ContractError::Unauthorized {})
+            } else {
+                Ok(())
+            }
+        }
+        None => Err(ContractError::Unauthorized {}),
+    }
+}
@@ -0,0 +1,172 @@
+use cosmwasm_std::Binary;
+use cw721::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+/// cw721-base ExecuteMsg where every token message names its collection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg<T> {
+    /// Creates a collection under this contract, anyone can create one.
+    /// The minter defaults to the sender.
+    CreateCollection {
+        collection_id: String,
+        name: String,
+        symbol: String,
+        minter: Option<String>,
+    },
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        collection_id: String,
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract, with ReceiveCollectionNft
+    SendNft {
+        collection_id: String,
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        collection_id: String,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        collection_id: String,
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token of the collection from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        collection_id: String,
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        collection_id: String,
+        operator: String,
+    },
+    /// Mint a new NFT, can only be called by the minter of the collection
+    Mint {
+        collection_id: String,
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: T,
+    },
+    /// Burn an NFT the sender has access to
+    Burn {
+        collection_id: String,
+        token_id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Lists the collections, ordered by collection_id
+    Collections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return the owner of the given token, error if token does not exist
+    OwnerOf {
+        collection_id: String,
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Return the approval of the spender on the given token
+    Approval {
+        collection_id: String,
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    /// Return approvals that a token has
+    Approvals {
+        collection_id: String,
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// List all operators that can access all of the owner's tokens in the collection
+    AllOperators {
+        collection_id: String,
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Total number of tokens issued in the collection
+    NumTokens { collection_id: String },
+    /// Collection level metadata
+    ContractInfo { collection_id: String },
+    /// Metadata of the given token
+    NftInfo {
+        collection_id: String,
+        token_id: String,
+    },
+    /// NftInfo and OwnerOf in one query
+    AllNftInfo {
+        collection_id: String,
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Tokens of the collection owned by the given address
+    Tokens {
+        collection_id: String,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// All tokens of the collection
+    AllTokens {
+        collection_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return the minter of the collection
+    Minter { collection_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionResponse {
+    pub collection_id: String,
+    pub name: String,
+    pub symbol: String,
+    pub minter: String,
+    pub num_tokens: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionsResponse {
+    pub collections: Vec<CollectionResponse>,
+}
+
+/// Sent to the receiving contract of SendNft.
+/// Same as Cw721ReceiveMsg with the collection, every collection shares the sender address.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionReceiveMsg {
+    pub collection_id: String,
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+/// Execute message of the receiving contract wrapping CollectionReceiveMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverExecuteMsg {
+    ReceiveCollectionNft(CollectionReceiveMsg),
+}
@@ -0,0 +1,281 @@
+use crate::error::ContractError;
+use crate::msg::{CollectionResponse, CollectionsResponse};
+use crate::state::{
+    collection_ids, get_token_metadata, get_tokens, load_collection, load_minter, load_token,
+    token_count, TokenInfo, OPERATORS,
+};
+use cosmwasm_std::{to_binary, Addr, Binary, BlockInfo, Deps, Env, Order, StdResult};
+use cw721::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Expiration, NftInfoResponse,
+    NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+};
+use cw721_base::MinterResponse;
+use cw721_simple_base::state::{Approval, TokenMetadata};
+use cw_storage_plus::{Bound, PrimaryKey};
+use cw_utils::maybe_addr;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+pub fn collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let collections = collection_ids(deps.storage, start_after.as_deref(), limit)?
+        .into_iter()
+        .map(|collection_id| {
+            let info = load_collection(deps.storage, &collection_id)?;
+            Ok(CollectionResponse {
+                name: info.name,
+                symbol: info.symbol,
+                minter: load_minter(deps.storage, &collection_id)?.to_string(),
+                num_tokens: token_count(deps.storage, &collection_id)?,
+                collection_id,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    to_binary(&CollectionsResponse { collections })
+}
+
+pub fn minter(deps: Deps, collection_id: String) -> StdResult<Binary> {
+    to_binary(&MinterResponse {
+        minter: load_minter(deps.storage, &collection_id)?.to_string(),
+    })
+}
+
+pub fn contract_info(deps: Deps, collection_id: String) -> StdResult<Binary> {
+    to_binary(&load_collection(deps.storage, &collection_id)?)
+}
+
+pub fn num_tokens(deps: Deps, collection_id: String) -> StdResult<Binary> {
+    load_collection(deps.storage, &collection_id)?;
+    to_binary(&NumTokensResponse {
+        count: token_count(deps.storage, &collection_id)?,
+    })
+}
+
+pub fn nft_info<T>(deps: Deps, collection_id: String, token_id: String) -> StdResult<Binary>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let metadata = load_token_metadata::<T>(deps, &collection_id, &token_id)?;
+    to_binary(&NftInfoResponse {
+        token_uri: metadata.token_uri,
+        extension: metadata.extension,
+    })
+}
+
+pub fn owner_of(
+    deps: Deps,
+    env: Env,
+    collection_id: String,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<Binary> {
+    let info = load_token(deps.storage, &collection_id, &token_id)?;
+    to_binary(&OwnerOfResponse {
+        owner: info.owner.to_string(),
+        approvals: humanize_approvals(&env.block, &info, include_expired),
+    })
+}
+
+fn load_token_metadata<T>(
+    deps: Deps,
+    collection_id: &str,
+    token_id: &str,
+) -> Result<TokenMetadata<T>, ContractError>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    get_token_metadata()
+        .may_load(deps.storage, (collection_id, token_id))?
+        .ok_or_else(|| ContractError::TokenNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+fn humanize_approvals(
+    block: &BlockInfo,
+    info: &TokenInfo,
+    include_expired: bool,
+) -> Vec<cw721::Approval> {
+    info.approvals
+        .iter()
+        .filter(|apr| include_expired || !apr.is_expired(block))
+        .map(humanize_approval)
+        .collect()
+}
+
+fn humanize_approval(approval: &Approval) -> cw721::Approval {
+    cw721::Approval {
+        spender: approval.spender.to_string(),
+        expires: approval.expires,
+    }
+}
+
+/// operators returns all operators owner given access to in the collection
+pub fn operators(
+    deps: Deps,
+    env: Env,
+    collection_id: String,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let res: StdResult<Vec<_>> = OPERATORS
+        .prefix((&collection_id, &owner_addr))
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block))
+        .take(limit)
+        .map(parse_approval)
+        .collect();
+    to_binary(&OperatorsResponse { operators: res? })
+}
+
+pub fn approval(
+    deps: Deps,
+    env: Env,
+    collection_id: String,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<Binary> {
+    let token = load_token(deps.storage, &collection_id, &token_id)?;
+
+    // token owner has absolute approval
+    if token.owner == spender {
+        let approval = cw721::Approval {
+            spender: token.owner.to_string(),
+            expires: Expiration::Never {},
+        };
+        return to_binary(&ApprovalResponse { approval });
+    }
+
+    let filtered: Vec<_> = token
+        .approvals
+        .into_iter()
+        .filter(|t| t.spender == spender)
+        .filter(|t| include_expired || !t.is_expired(&env.block))
+        .map(|a| cw721::Approval {
+            spender: a.spender.into_string(),
+            expires: a.expires,
+        })
+        .collect();
+
+    if filtered.is_empty() {
+        return Err(ContractError::ApprovalNotFound { spender }.into());
+    }
+    // we expect only one item
+    let approval = filtered[0].clone();
+
+    to_binary(&ApprovalResponse { approval })
+}
+
+/// approvals returns all approvals owner given access to
+pub fn approvals(
+    deps: Deps,
+    env: Env,
+    collection_id: String,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<Binary> {
+    let token = load_token(deps.storage, &collection_id, &token_id)?;
+    let approvals: Vec<_> = token
+        .approvals
+        .into_iter()
+        .filter(|t| include_expired || !t.is_expired(&env.block))
+        .map(|a| cw721::Approval {
+            spender: a.spender.into_string(),
+            expires: a.expires,
+        })
+        .collect();
+
+    to_binary(&ApprovalsResponse { approvals })
+}
+
+pub fn tokens(
+    deps: Deps,
+    collection_id: String,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // the owner index is keyed by the full (collection_id, token_id) primary key
+    let start =
+        start_after.map(|s| Bound::ExclusiveRaw((collection_id.as_str(), s.as_str()).joined_key()));
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let tokens: Vec<String> = get_tokens()
+        .idx
+        .owner
+        .prefix((collection_id.clone(), owner_addr))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, token_id)| token_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    to_binary(&TokensResponse { tokens })
+}
+
+pub fn all_tokens(
+    deps: Deps,
+    collection_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens: StdResult<Vec<String>> = get_tokens()
+        .prefix(&collection_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(k, _)| k))
+        .collect();
+
+    to_binary(&TokensResponse { tokens: tokens? })
+}
+
+pub fn all_nft_info<T>(
+    deps: Deps,
+    env: Env,
+    collection_id: String,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<Binary>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let info = load_token(deps.storage, &collection_id, &token_id)?;
+    let metadata = load_token_metadata::<T>(deps, &collection_id, &token_id)?;
+    to_binary(&AllNftInfoResponse {
+        access: OwnerOfResponse {
+            owner: info.owner.to_string(),
+            approvals: humanize_approvals(&env.block, &info, include_expired),
+        },
+        info: NftInfoResponse {
+            token_uri: metadata.token_uri,
+            extension: metadata.extension,
+        },
+    })
+}
+
+fn parse_approval(item: StdResult<(Addr, Expiration)>) -> StdResult<cw721::Approval> {
+    item.map(|(spender, expires)| cw721::Approval {
+        spender: spender.to_string(),
+        expires,
+    })
+}
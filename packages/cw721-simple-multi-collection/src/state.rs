@@ -0,0 +1,125 @@
+use crate::error::ContractError;
+use cosmwasm_std::{Addr, Order, OverflowError, OverflowOperation, StdResult, Storage};
+use cw721::{ContractInfoResponse, Expiration};
+use cw721_simple_base::state::{Approval, TokenMetadata};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, MultiIndex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const CONTRACT_INFO_KEY: &str = "collections";
+const MINTER_KEY: &str = "collection_minters";
+const TOKEN_COUNT_KEY: &str = "collection_num_tokens";
+const OPERATORS_KEY: &str = "collection_operators";
+const TOKENS_KEY: &str = "collection_tokens";
+const TOKENS_OWNER_KEY: &str = "collection_tokens__owner";
+const TOKEN_METADATA_KEY: &str = "collection_token_metadata";
+
+/// Every item of cw721-simple-base::state, namespaced by collection_id
+pub const CONTRACT_INFO: Map<&str, ContractInfoResponse> = Map::new(CONTRACT_INFO_KEY);
+pub const MINTER: Map<&str, Addr> = Map::new(MINTER_KEY);
+pub const TOKENS_COUNT: Map<&str, u64> = Map::new(TOKEN_COUNT_KEY);
+/// (collection_id, owner, operator)
+pub const OPERATORS: Map<(&str, &Addr, &Addr), Expiration> = Map::new(OPERATORS_KEY);
+
+/// Ownership record of a token in a collection.
+/// The collection is repeated in the record, so the owner index can be namespaced by it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInfo {
+    pub collection_id: String,
+    /// The owner of the newly minted NFT
+    pub owner: Addr,
+    /// Approvals are stored here, as we clear them all upon transfer and cannot accumulate much
+    pub approvals: Vec<Approval>,
+}
+
+pub struct TokenIndexes<'a> {
+    // (collection_id, owner) -> (collection_id, token_id)
+    pub owner: MultiIndex<'a, (String, Addr), TokenInfo, (String, String)>,
+}
+
+impl<'a> IndexList<TokenInfo> for TokenIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenInfo>> + '_> {
+        let v: Vec<&dyn Index<TokenInfo>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// tokens keyed by (collection_id, token_id)
+pub fn get_tokens<'a>() -> IndexedMap<'a, (&'a str, &'a str), TokenInfo, TokenIndexes<'a>> {
+    let indexes = TokenIndexes {
+        owner: MultiIndex::new(
+            |d: &TokenInfo| (d.collection_id.clone(), d.owner.clone()),
+            TOKENS_KEY,
+            TOKENS_OWNER_KEY,
+        ),
+    };
+    IndexedMap::new(TOKENS_KEY, indexes)
+}
+
+/// metadata keyed by (collection_id, token_id)
+pub fn get_token_metadata<'a, T>() -> Map<'a, (&'a str, &'a str), TokenMetadata<T>> {
+    Map::new(TOKEN_METADATA_KEY)
+}
+
+pub fn load_token(
+    storage: &dyn Storage,
+    collection_id: &str,
+    token_id: &str,
+) -> Result<TokenInfo, ContractError> {
+    get_tokens()
+        .may_load(storage, (collection_id, token_id))?
+        .ok_or_else(|| ContractError::TokenNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// errors with CollectionNotFound unless the collection was created
+pub fn load_collection(
+    storage: &dyn Storage,
+    collection_id: &str,
+) -> Result<ContractInfoResponse, ContractError> {
+    CONTRACT_INFO
+        .may_load(storage, collection_id)?
+        .ok_or_else(|| ContractError::CollectionNotFound {
+            collection_id: collection_id.to_string(),
+        })
+}
+
+pub fn load_minter(storage: &dyn Storage, collection_id: &str) -> Result<Addr, ContractError> {
+    load_collection(storage, collection_id)?;
+    Ok(MINTER.load(storage, collection_id)?)
+}
+
+pub fn collection_ids(
+    storage: &dyn Storage,
+    start_after: Option<&str>,
+    limit: usize,
+) -> StdResult<Vec<String>> {
+    let start = start_after.map(|s| cw_storage_plus::Bound::ExclusiveRaw(s.into()));
+    CONTRACT_INFO
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn token_count(storage: &dyn Storage, collection_id: &str) -> StdResult<u64> {
+    Ok(TOKENS_COUNT
+        .may_load(storage, collection_id)?
+        .unwrap_or_default())
+}
+
+pub fn increment_tokens(storage: &mut dyn Storage, collection_id: &str) -> StdResult<u64> {
+    let val = token_count(storage, collection_id)?
+        .checked_add(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, u64::MAX, 1))?;
+    TOKENS_COUNT.save(storage, collection_id, &val)?;
+    Ok(val)
+}
+
+pub fn decrement_tokens(storage: &mut dyn Storage, collection_id: &str) -> StdResult<u64> {
+    let val = token_count(storage, collection_id)?
+        .checked_sub(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, 0, 1))?;
+    TOKENS_COUNT.save(storage, collection_id, &val)?;
+    Ok(val)
+}
@@ -0,0 +1,260 @@
+//! cw-multi-test harness for the example contracts built on cw721-simple-base
+//!
+//! Every example contract is wrapped as a `ContractWrapper`, next to a receiver contract
+//! accepting or rejecting the NFTs sent to it, so `SendNft` runs end to end.
+
+pub mod receiver;
+
+use cosmwasm_std::Empty;
+use cw_multi_test::{Contract, ContractWrapper};
+
+pub fn cw721_simple_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw721_simple::execute,
+        cw721_simple::instantiate,
+        cw721_simple::query,
+    ))
+}
+
+pub fn cw721_simple_metadata_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw721_simple_metadata::execute,
+        cw721_simple_metadata::instantiate,
+        cw721_simple_metadata::query,
+    ))
+}
+
+pub fn cw721_simple_metadata_without_custom_msg_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw721_simple_metadata_without_custom_msg::contract::execute,
+        cw721_simple_metadata_without_custom_msg::contract::instantiate,
+        cw721_simple_metadata_without_custom_msg::contract::query,
+    ))
+}
+
+pub fn receiver_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        receiver::execute,
+        receiver::instantiate,
+        receiver::query,
+    ))
+}
+
+#[cfg(test)]
+mod multi_tests {
+    use crate::receiver::{self, ReceivedResponse, ReceivedToken, ReceiverMsg};
+    use crate::{
+        cw721_simple_contract, cw721_simple_metadata_contract,
+        cw721_simple_metadata_without_custom_msg_contract, receiver_contract,
+    };
+    use cosmwasm_std::{to_binary, Addr, Binary, Empty};
+    use cw721::{Approval, Expiration, NumTokensResponse, OwnerOfResponse};
+    use cw721_base::{ExecuteMsg, InstantiateMsg, MintMsg, QueryMsg};
+    use cw_multi_test::{App, Contract, Executor};
+
+    const MINTER: &str = "juno1qwmzgaqa8ztlyvmedkyqxwd2xz0zgmwz8qxvmk";
+    const ADDR1: &str = "juno18zfp9u7zxg3gel4r3txa2jqxme7jkw7d972flm";
+    const ADDR2: &str = "osmo18zfp9u7zxg3gel4r3txa2jqxme7jkw7dmh6zw4";
+
+    // every example contract accepts a mint without metadata
+    type Extension = Option<Empty>;
+    type ContractFn = fn() -> Box<dyn Contract<Empty>>;
+
+    struct Suite {
+        app: App,
+        cw721: Addr,
+        receiver: Addr,
+    }
+
+    impl Suite {
+        fn new(cw721_contract: Box<dyn Contract<Empty>>) -> Self {
+            let mut app = App::default();
+            let cw721_code_id = app.store_code(cw721_contract);
+            let receiver_code_id = app.store_code(receiver_contract());
+
+            let cw721 = app
+                .instantiate_contract(
+                    cw721_code_id,
+                    Addr::unchecked(MINTER),
+                    &InstantiateMsg {
+                        name: "cw721-contract".to_string(),
+                        symbol: "cw721".to_string(),
+                        minter: MINTER.to_string(),
+                    },
+                    &[],
+                    "cw721",
+                    None,
+                )
+                .unwrap();
+            let receiver = app
+                .instantiate_contract(
+                    receiver_code_id,
+                    Addr::unchecked(MINTER),
+                    &receiver::InstantiateMsg {},
+                    &[],
+                    "receiver",
+                    None,
+                )
+                .unwrap();
+
+            let mut suite = Suite {
+                app,
+                cw721,
+                receiver,
+            };
+            suite
+                .execute(
+                    MINTER,
+                    ExecuteMsg::Mint(MintMsg {
+                        token_id: "1".to_string(),
+                        owner: ADDR1.to_string(),
+                        token_uri: None,
+                        extension: None,
+                    }),
+                )
+                .unwrap();
+            suite
+        }
+
+        fn execute(
+            &mut self,
+            sender: &str,
+            msg: ExecuteMsg<Extension, Empty>,
+        ) -> anyhow::Result<()> {
+            self.app
+                .execute_contract(Addr::unchecked(sender), self.cw721.clone(), &msg, &[])
+                .map(|_| ())
+        }
+
+        fn send_nft(&mut self, sender: &str, msg: Binary) -> anyhow::Result<()> {
+            self.execute(
+                sender,
+                ExecuteMsg::SendNft {
+                    contract: self.receiver.to_string(),
+                    token_id: "1".to_string(),
+                    msg,
+                },
+            )
+        }
+
+        fn owner_of(&self) -> OwnerOfResponse {
+            self.app
+                .wrap()
+                .query_wasm_smart(
+                    self.cw721.clone(),
+                    &QueryMsg::<Empty>::OwnerOf {
+                        token_id: "1".to_string(),
+                        include_expired: None,
+                    },
+                )
+                .unwrap()
+        }
+
+        fn received(&self) -> Vec<ReceivedToken> {
+            let res: ReceivedResponse = self
+                .app
+                .wrap()
+                .query_wasm_smart(self.receiver.clone(), &receiver::QueryMsg::Received {})
+                .unwrap();
+            res.tokens
+        }
+    }
+
+    fn contracts() -> Vec<ContractFn> {
+        vec![
+            cw721_simple_contract,
+            cw721_simple_metadata_contract,
+            cw721_simple_metadata_without_custom_msg_contract,
+        ]
+    }
+
+    #[test]
+    fn test_send_accepted() {
+        for contract in contracts() {
+            let mut suite = Suite::new(contract());
+
+            suite
+                .send_nft(ADDR1, to_binary(&ReceiverMsg::Accept {}).unwrap())
+                .unwrap();
+
+            assert_eq!(suite.owner_of().owner, suite.receiver.as_str());
+            assert_eq!(
+                suite.received(),
+                vec![ReceivedToken {
+                    collection: suite.cw721.to_string(),
+                    token_id: "1".to_string(),
+                    sender: ADDR1.to_string(),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_send_rejected_reverts_ownership() {
+        for contract in contracts() {
+            let mut suite = Suite::new(contract());
+            suite
+                .execute(
+                    ADDR1,
+                    ExecuteMsg::Approve {
+                        spender: ADDR2.to_string(),
+                        token_id: "1".to_string(),
+                        expires: None,
+                    },
+                )
+                .unwrap();
+
+            let err = suite
+                .send_nft(ADDR2, to_binary(&ReceiverMsg::Reject {}).unwrap())
+                .unwrap_err();
+            assert_eq!(err.root_cause().to_string(), "Rejected token: 1");
+
+            // the transfer and the cleared approvals are rolled back with the receive
+            assert_eq!(
+                suite.owner_of(),
+                OwnerOfResponse {
+                    owner: ADDR1.to_string(),
+                    approvals: vec![Approval {
+                        spender: ADDR2.to_string(),
+                        expires: Expiration::Never {},
+                    }],
+                }
+            );
+            assert_eq!(suite.received(), vec![]);
+
+            let num_tokens: NumTokensResponse = suite
+                .app
+                .wrap()
+                .query_wasm_smart(suite.cw721.clone(), &QueryMsg::<Empty>::NumTokens {})
+                .unwrap();
+            assert_eq!(num_tokens.count, 1);
+        }
+    }
+
+    #[test]
+    fn test_send_unknown_payload_reverts_ownership() {
+        let mut suite = Suite::new(cw721_simple_contract());
+
+        suite
+            .send_nft(ADDR1, to_binary("hello").unwrap())
+            .unwrap_err();
+
+        assert_eq!(suite.owner_of().owner, ADDR1);
+        assert_eq!(suite.received(), vec![]);
+    }
+
+    #[test]
+    fn test_send_to_receiver_twice() {
+        let mut suite = Suite::new(cw721_simple_contract());
+        suite
+            .send_nft(ADDR1, to_binary(&ReceiverMsg::Accept {}).unwrap())
+            .unwrap();
+
+        // the previous owner lost access with the first send
+        let err = suite
+            .send_nft(ADDR1, to_binary(&ReceiverMsg::Accept {}).unwrap())
+            .unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "Unauthorized");
+        assert_eq!(suite.owner_of().owner, suite.receiver.as_str());
+    }
+}
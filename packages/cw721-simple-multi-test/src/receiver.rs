@@ -0,0 +1,111 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+    StdResult,
+};
+use cw721::Cw721ReceiveMsg;
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// token_id -> (collection, previous owner) of every accepted NFT
+const RECEIVED: Map<&str, (String, String)> = Map::new("received");
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Rejected token: {token_id}")]
+    Rejected { token_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InstantiateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+/// Payload of SendNft deciding whether the receiver keeps the token
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverMsg {
+    Accept {},
+    Reject {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Received {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReceivedResponse {
+    pub tokens: Vec<ReceivedToken>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReceivedToken {
+    pub collection: String,
+    pub token_id: String,
+    pub sender: String,
+}
+
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender,
+            token_id,
+            msg,
+        }) => match from_binary(&msg)? {
+            ReceiverMsg::Accept {} => {
+                RECEIVED.save(
+                    deps.storage,
+                    &token_id,
+                    &(info.sender.to_string(), sender.clone()),
+                )?;
+                Ok(Response::new()
+                    .add_attribute("action", "receive_nft")
+                    .add_attribute("sender", sender)
+                    .add_attribute("token_id", token_id))
+            }
+            ReceiverMsg::Reject {} => Err(ContractError::Rejected { token_id }),
+        },
+    }
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Received {} => {
+            let tokens = RECEIVED
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    item.map(|(token_id, (collection, sender))| ReceivedToken {
+                        collection,
+                        token_id,
+                        sender,
+                    })
+                })
+                .collect::<StdResult<_>>()?;
+            to_binary(&ReceivedResponse { tokens })
+        }
+    }
+}